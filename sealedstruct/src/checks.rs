@@ -0,0 +1,118 @@
+//! Checks used by the `#[sealed(...)]` field attributes of `derive(TryIntoNested)`
+//!
+//! They can also be called from handwritten `TryIntoNested` or `Validator` implementations.
+//! All errors are reported without a field, so the caller can prepend the path of the field.
+//! Their codes are `range.min`, `range.max`, `length.min`, `length.max` and `non_empty`,
+//! with the violated bound and the `actual` value as parameters.
+
+use crate::{Result, ValidationError};
+use std::collections::{HashMap, HashSet};
+
+/// Fails if `value` is not within `min..=max`. Missing bounds are not checked.
+pub fn range<T: PartialOrd + std::fmt::Display>(
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<()> {
+    match (min, max) {
+        (Some(min), _) if *value < min => {
+            ValidationError::new(format!("must be at least {min}, got {value}"))
+                .with_code("range.min")
+                .with_param("min", min)
+                .with_param("actual", value)
+                .into()
+        }
+        (_, Some(max)) if *value > max => {
+            ValidationError::new(format!("must be at most {max}, got {value}"))
+                .with_code("range.max")
+                .with_param("max", max)
+                .with_param("actual", value)
+                .into()
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the length of `value` is not within `min..=max`. Missing bounds are not checked.
+pub fn length<T: HasLength + ?Sized>(
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<()> {
+    let len = value.length();
+    match (min, max) {
+        (Some(min), _) if len < min => {
+            ValidationError::new(format!("length must be at least {min}, got {len}"))
+                .with_code("length.min")
+                .with_param("min", min)
+                .with_param("actual", len)
+                .into()
+        }
+        (_, Some(max)) if len > max => {
+            ValidationError::new(format!("length must be at most {max}, got {len}"))
+                .with_code("length.max")
+                .with_param("max", max)
+                .with_param("actual", len)
+                .into()
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails if `value` has a length of 0
+pub fn non_empty<T: HasLength + ?Sized>(value: &T) -> Result<()> {
+    if value.length() == 0 {
+        ValidationError::new("must not be empty")
+            .with_code("non_empty")
+            .into()
+    } else {
+        Ok(())
+    }
+}
+
+/// Length used by [length] and [non_empty]. Strings are measured in chars, not bytes.
+pub trait HasLength {
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T: HasLength + ?Sized> HasLength for &T {
+    fn length(&self) -> usize {
+        T::length(self)
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<TKey, TValue> HasLength for HashMap<TKey, TValue> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for HashSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
@@ -0,0 +1,67 @@
+//! Fields, which differ between a raw and a sealed value, e.g. for audit logs or to detect unsaved changes
+//!
+//! Paths are the same as those of the errors of [Sealable::seal](crate::Sealable::seal).
+
+use crate::{FieldPath, PathSegment};
+
+/// Kind of a [FieldChange]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Both values exist, but differ
+    Changed,
+    /// Only exists in the raw value, e.g. an additional item or key
+    Added,
+    /// Only exists in the sealed value
+    Removed,
+}
+
+/// Returned by [Sealable::diff](crate::Sealable::diff)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldChange {
+    pub field: FieldPath,
+    pub kind: ChangeKind,
+}
+
+impl FieldChange {
+    /// Change of the value itself
+    pub fn new(kind: ChangeKind) -> Self {
+        Self {
+            field: FieldPath::root(),
+            kind,
+        }
+    }
+
+    pub fn prepend(mut self, segment: PathSegment) -> Self {
+        self.field.prepend(segment);
+        self
+    }
+}
+
+/// Prepends the segment to the fields of all changes
+pub fn prepend(changes: Vec<FieldChange>, segment: PathSegment) -> Vec<FieldChange> {
+    changes
+        .into_iter()
+        .map(|change| change.prepend(segment.clone()))
+        .collect()
+}
+
+pub fn prepend_field(changes: Vec<FieldChange>, name: &'static str) -> Vec<FieldChange> {
+    prepend(changes, PathSegment::Field(name.into()))
+}
+
+pub fn prepend_variant(changes: Vec<FieldChange>, name: &'static str) -> Vec<FieldChange> {
+    prepend(changes, PathSegment::Variant(name.into()))
+}
+
+pub fn prepend_index(changes: Vec<FieldChange>, index: usize) -> Vec<FieldChange> {
+    prepend(changes, PathSegment::Index(index))
+}
+
+/// A single [ChangeKind::Changed] on the value itself, unless both are equal
+pub(crate) fn changed_unless(equal: bool) -> Vec<FieldChange> {
+    if equal {
+        Vec::new()
+    } else {
+        vec![FieldChange::new(ChangeKind::Changed)]
+    }
+}
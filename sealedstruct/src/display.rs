@@ -0,0 +1,196 @@
+//! Human readable output of [ValidationErrors]
+//!
+//! Fields are always listed in the order of their first error, so the output is deterministic.
+
+use crate::{ErrorTree, FieldPath, PathSegment, ValidationErrors};
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
+
+/// Style used by [ValidationErrors::display_with]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Single line, limited to the first 5 fields: `ValidationErrors(2): {a: ["x"], b: ["y"]}`
+    ///
+    /// Used by `Display`.
+    #[default]
+    Compact,
+    /// Single line like [Format::Compact], but lists all fields
+    Full,
+    /// One line per reason, with fields nested below their parents
+    ///
+    /// ```text
+    /// ValidationErrors(2):
+    ///   numbers:
+    ///     [3]:
+    ///       int8: must be <100
+    ///   name: must not be empty
+    /// ```
+    Tree,
+}
+
+/// Returned by [ValidationErrors::display_with]
+pub struct DisplayWith<'a> {
+    errors: &'a ValidationErrors,
+    format: Format,
+}
+
+const COMPACT_FIELDS: usize = 5;
+
+impl ValidationErrors {
+    pub fn display_with(&self, format: Format) -> DisplayWith<'_> {
+        DisplayWith {
+            errors: self,
+            format,
+        }
+    }
+}
+
+impl fmt::Display for DisplayWith<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.format {
+            Format::Compact => fmt_line(self.errors, Some(COMPACT_FIELDS), f),
+            Format::Full => fmt_line(self.errors, None, f),
+            Format::Tree => fmt_tree(self.errors, f),
+        }
+    }
+}
+
+/// Reasons of each field, ordered by the first error of the field
+pub(crate) fn group_by_field(errors: &ValidationErrors) -> Vec<(&FieldPath, Vec<&str>)> {
+    let mut positions = HashMap::new();
+    let mut groups: Vec<(&FieldPath, Vec<&str>)> = Vec::new();
+    for error in errors.iter() {
+        for field in error.iter_fields() {
+            let position = *positions.entry(field).or_insert_with(|| {
+                groups.push((field, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(error.reason.as_str());
+        }
+    }
+    groups
+}
+
+fn fmt_line(
+    errors: &ValidationErrors,
+    limit: Option<usize>,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    write!(f, "ValidationErrors({}): {{", errors.0.len())?;
+    let groups = group_by_field(errors);
+    let shown = limit.unwrap_or(groups.len()).min(groups.len());
+    for (position, (field, reasons)) in groups[..shown].iter().enumerate() {
+        if position > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{field}: {reasons:?}")?;
+    }
+    if shown < groups.len() {
+        f.write_str(", ...")?;
+    }
+    f.write_char('}')
+}
+
+/// Starts each line with a newline, so the output doesn't end with one
+fn fmt_node(
+    label: &str,
+    node: &ErrorTree,
+    depth: usize,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    let indent = depth * 2;
+    for reason in node.reasons() {
+        write!(f, "\n{:indent$}{}: {}", "", label, reason)?;
+    }
+    if node.errors().is_empty() {
+        write!(f, "\n{:indent$}{}:", "", label)?;
+    }
+    fmt_children(node, depth + 1, f)
+}
+
+fn fmt_children(node: &ErrorTree, depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (segment, child) in node.children() {
+        fmt_node(&segment_label(segment), child, depth, f)?;
+    }
+    Ok(())
+}
+
+fn segment_label(segment: &PathSegment) -> String {
+    match segment {
+        PathSegment::Field(name) | PathSegment::Variant(name) => name.to_string(),
+        PathSegment::Index(index) => format!("[{index}]"),
+        PathSegment::Key(key) => format!("[{key:?}]"),
+    }
+}
+
+fn fmt_tree(errors: &ValidationErrors, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let root = errors.to_tree();
+    write!(f, "ValidationErrors({}):", errors.0.len())?;
+    // Errors without a field are listed first, without a label
+    for reason in root.reasons() {
+        write!(f, "\n  {reason}")?;
+    }
+    fmt_children(&root, 1, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Result, ValidationError, ValidationResultExtensions};
+
+    fn errors() -> ValidationErrors {
+        let int8: Result<()> = ValidationError::new("must be <100").into();
+        let int16: Result<()> = ValidationError::new("max is not allowed").into();
+        let numbers = int8
+            .prepend_path("int8")
+            .combine(int16.prepend_path("int16"))
+            .prepend_index(3)
+            .prepend_path("numbers");
+        let name: Result<()> = ValidationError::on_field("name", "must not be empty").into();
+        let root: Result<()> = ValidationError::new("is locked").into();
+        let again: Result<()> = ValidationError::on_field("name", "is reserved").into();
+        numbers
+            .combine(name)
+            .combine(root)
+            .combine(again)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn compact_is_ordered_and_truncated() {
+        assert_eq!(
+            r#"ValidationErrors(5): {numbers[3].int8: ["must be <100"], numbers[3].int16: ["max is not allowed"], name: ["must not be empty", "is reserved"], : ["is locked"]}"#,
+            errors().to_string()
+        );
+
+        let many = (0..7)
+            .map(|x| ValidationErrors::new(ValidationError::on_field(format!("f{x}"), "bad")))
+            .reduce(ValidationErrors::combine_with)
+            .unwrap();
+        assert_eq!(
+            r#"ValidationErrors(7): {f0: ["bad"], f1: ["bad"], f2: ["bad"], f3: ["bad"], f4: ["bad"], ...}"#,
+            many.to_string()
+        );
+        assert_eq!(
+            r#"ValidationErrors(7): {f0: ["bad"], f1: ["bad"], f2: ["bad"], f3: ["bad"], f4: ["bad"], f5: ["bad"], f6: ["bad"]}"#,
+            many.display_with(Format::Full).to_string()
+        );
+    }
+
+    #[test]
+    fn tree() {
+        assert_eq!(
+            "ValidationErrors(5):
+  is locked
+  numbers:
+    [3]:
+      int8: must be <100
+      int16: max is not allowed
+  name: must not be empty
+  name: is reserved",
+            errors().display_with(Format::Tree).to_string()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Overriding fields of raw values from environment variables
+//!
+//! Variables are named after the fields in upper case, separated by `__`, e.g. `APP__NUMBERS__INT8=5`
+//! for the field `numbers.int8` with the prefix `APP`. Leaf values are parsed with `FromStr`.
+//! `FromEnv` is derived by `Nested` and `Seal` for structs with named fields and `#[sealed(env)]`.
+//!
+//! Variables are passed explicitly, so tests don't depend on the process environment:
+//!
+//! ```
+//! #[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+//! #[sealed(env)]
+//! pub struct ServerRaw {
+//!     port: u16,
+//!     name: Option<String>,
+//! }
+//!
+//! let vars = [("APP__PORT".to_string(), "8080".to_string())].into_iter().collect();
+//! let raw = ServerRaw { port: 80, name: None };
+//! let server = sealedstruct::env::seal(raw, "APP", &vars).unwrap();
+//! assert_eq!(8080, server.port);
+//! ```
+
+use crate::{FieldPath, PathSegment, Result, Sealable, ValidationError, ValidationErrors};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// Raw types, whose fields can be overridden by environment variables
+pub trait FromEnv {
+    /// Overrides the value with the variable `name` or its fields with variables starting with `{name}__`
+    ///
+    /// All variables are applied, even if some of them can't be parsed. These are reported on their field
+    /// with the param `variable`.
+    fn override_from(&mut self, vars: &HashMap<String, String>, name: &str) -> Result<()>;
+}
+
+/// Name of the variable of a field, e.g. `APP__NUMBERS` for the field `numbers` and the name `APP`
+pub fn join(name: &str, field: &str) -> String {
+    let field = field.to_uppercase();
+    if name.is_empty() {
+        field
+    } else {
+        format!("{name}__{field}")
+    }
+}
+
+/// Name of the variable of a field path. Returns `None`, if the path contains an index or a key.
+pub fn variable(prefix: &str, field: &FieldPath) -> Option<String> {
+    field
+        .segments()
+        .iter()
+        .try_fold(prefix.to_string(), |name, segment| match segment {
+            PathSegment::Field(field) => Some(join(&name, field.trim_start_matches("r#"))),
+            _ => None,
+        })
+}
+
+/// Overrides the fields of `raw` with the variables starting with `{prefix}__`
+pub fn overlay<T: FromEnv>(
+    raw: &mut T,
+    prefix: &str,
+    vars: &HashMap<String, String>,
+) -> Result<()> {
+    raw.override_from(vars, prefix)
+}
+
+/// Overrides the fields of `raw` and seals it
+///
+/// Parse failures and validation failures are reported together. Each error gets the param `variable`
+/// with the name of the variable of its first field.
+pub fn seal<T: FromEnv + Sealable>(
+    mut raw: T,
+    prefix: &str,
+    vars: &HashMap<String, String>,
+) -> Result<T::Target> {
+    let overridden = overlay(&mut raw, prefix, vars);
+    let sealed = raw.seal().map_err(|errors| {
+        ValidationErrors(
+            errors
+                .into_iter()
+                .map(|error| {
+                    let variable = error
+                        .iter_fields()
+                        .find_map(|field| variable(prefix, field));
+                    match variable {
+                        Some(variable) => error.with_param("variable", variable),
+                        None => error,
+                    }
+                })
+                .collect(),
+        )
+    });
+    match (overridden, sealed) {
+        (Ok(()), sealed) => sealed,
+        (Err(errors), Ok(_)) => Err(errors),
+        (Err(errors), Err(other)) => Err(errors.combine_with(other)),
+    }
+}
+
+/// Variables of the current process
+pub fn process_vars() -> HashMap<String, String> {
+    std::env::vars().collect()
+}
+
+/// Parses the variable `name`, if it is set
+pub fn override_parsed<T>(value: &mut T, vars: &HashMap<String, String>, name: &str) -> Result<()>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Some(text) = vars.get(name) else {
+        return Ok(());
+    };
+    match text.parse() {
+        Ok(parsed) => {
+            *value = parsed;
+            Ok(())
+        }
+        Err(e) => ValidationError::new(format!("invalid value {text:?}: {e}"))
+            .with_code("env.parse")
+            .with_param("variable", name)
+            .into(),
+    }
+}
+
+macro_rules! parse_from_env {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl FromEnv for $type {
+                fn override_from(&mut self, vars: &HashMap<String, String>, name: &str) -> Result<()> {
+                    override_parsed(self, vars, name)
+                }
+            }
+        )*
+    };
+}
+
+parse_from_env! {
+    u8, u16, u32, u64, u128,
+    i8, i16, i32, i64, i128,
+    f32, f64,
+    usize, isize,
+    std::num::NonZeroU8, std::num::NonZeroU16, std::num::NonZeroU32, std::num::NonZeroU64, std::num::NonZeroU128,
+    std::num::NonZeroI8, std::num::NonZeroI16, std::num::NonZeroI32, std::num::NonZeroI64, std::num::NonZeroI128,
+    bool,
+    String,
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+}
+
+#[cfg(feature = "uuid")]
+parse_from_env! { uuid::Uuid }
+
+#[cfg(feature = "chrono")]
+parse_from_env! { chrono::DateTime<chrono::Utc> }
+
+/// A missing value is created from its default, if any of its variables is set
+impl<T: FromEnv + Default> FromEnv for Option<T> {
+    fn override_from(&mut self, vars: &HashMap<String, String>, name: &str) -> Result<()> {
+        let nested = format!("{name}__");
+        if !vars
+            .keys()
+            .any(|key| key == name || key.starts_with(&nested))
+        {
+            return Ok(());
+        }
+        let was_some = self.is_some();
+        let mut value = self.take().unwrap_or_default();
+        let result = value.override_from(vars, name);
+        // A value, which couldn't be parsed, stays missing
+        if was_some || result.is_ok() {
+            *self = Some(value);
+        }
+        result
+    }
+}
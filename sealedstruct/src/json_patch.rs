@@ -0,0 +1,206 @@
+//! JSON Patch according to RFC 6902, applied to sealed values
+//!
+//! ```json
+//! [
+//!   { "op": "replace", "path": "/numbers/3/int8", "value": 42 },
+//!   { "op": "remove", "path": "/name" }
+//! ]
+//! ```
+//!
+//! The patch is applied to the JSON representation of the raw value, which is sealed again afterwards.
+//! Failing operations are reported on the field of their pointer, e.g. `numbers[3].int8`,
+//! so they can be shown like the errors of sealing.
+
+use crate::{FieldPath, Result, Sealable, Sealed, ValidationError, ValidationErrors};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Single operation of a JSON Patch document. Paths are JSON Pointers according to RFC 6901.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl Operation {
+    pub fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Move { path, .. }
+            | Operation::Copy { path, .. }
+            | Operation::Test { path, .. } => path,
+        }
+    }
+}
+
+/// Opens the sealed value, applies all operations to its raw value and seals it again
+///
+/// Like RFC 6902 requires, the patch is applied completely or not at all: The first failing operation
+/// is returned as error. If the patched document doesn't match the raw type, the error is reported
+/// on the paths of all operations. Errors of sealing keep their fields.
+pub fn apply<T>(sealed: T, patch: &[Operation]) -> Result<T>
+where
+    T: Sealed,
+    T::Raw: Serialize + DeserializeOwned,
+{
+    let raw = T::Raw::open(sealed);
+    let mut document = serde_json::to_value(&raw).map_err(|e| {
+        ValidationErrors::new(ValidationError::new(e.to_string()).with_code("json_patch.serialize"))
+    })?;
+    for operation in patch {
+        apply_operation(&mut document, operation)?;
+    }
+    let raw = serde_json::from_value::<T::Raw>(document).map_err(|e| {
+        let mut fields = patch
+            .iter()
+            .map(|operation| pointer_field(operation.path()));
+        let error = match fields.next() {
+            Some(first) => ValidationError::on_fields(first, fields, e.to_string()),
+            None => ValidationError::new(e.to_string()),
+        };
+        ValidationErrors::new(error.with_code("json_patch.deserialize"))
+    })?;
+    raw.seal()
+}
+
+fn apply_operation(
+    document: &mut Value,
+    operation: &Operation,
+) -> std::result::Result<(), ValidationError> {
+    match operation {
+        Operation::Add { path, value } => add(document, path, value.clone()),
+        Operation::Remove { path } => remove(document, path).map(|_| ()),
+        Operation::Replace { path, value } => {
+            *get_mut(document, path)? = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            if from == path {
+                return get_mut(document, from).map(|_| ());
+            }
+            if path.starts_with(&format!("{from}/")) {
+                return Err(error(
+                    path,
+                    "a value can't be moved into one of its children",
+                    "json_patch.invalid_move",
+                ));
+            }
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        Operation::Copy { from, path } => {
+            let value = get_mut(document, from)?.clone();
+            add(document, path, value)
+        }
+        Operation::Test { path, value } => {
+            if get_mut(document, path)? == value {
+                Ok(())
+            } else {
+                Err(error(path, "value doesn't match", "json_patch.test"))
+            }
+        }
+    }
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> std::result::Result<(), ValidationError> {
+    if path.is_empty() {
+        *document = value;
+        return Ok(());
+    }
+    let (parent, token) = split_pointer(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(fields)) => {
+            fields.insert(token, value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = match token.as_str() {
+                "-" => items.len(),
+                _ => parse_index(&token)
+                    .filter(|index| *index <= items.len())
+                    .ok_or_else(|| invalid_index(path))?,
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(not_found(path)),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> std::result::Result<Value, ValidationError> {
+    if path.is_empty() {
+        return Err(error(
+            path,
+            "the whole document can't be removed",
+            "json_patch.invalid_pointer",
+        ));
+    }
+    let (parent, token) = split_pointer(path)?;
+    match document.pointer_mut(parent) {
+        Some(Value::Object(fields)) => fields.remove(&token).ok_or_else(|| not_found(path)),
+        Some(Value::Array(items)) => {
+            let index = parse_index(&token)
+                .filter(|index| *index < items.len())
+                .ok_or_else(|| invalid_index(path))?;
+            Ok(items.remove(index))
+        }
+        _ => Err(not_found(path)),
+    }
+}
+
+fn get_mut<'a>(
+    document: &'a mut Value,
+    path: &str,
+) -> std::result::Result<&'a mut Value, ValidationError> {
+    if !path.is_empty() {
+        split_pointer(path)?;
+    }
+    document.pointer_mut(path).ok_or_else(|| not_found(path))
+}
+
+/// Returns the pointer of the parent and the unescaped last token
+fn split_pointer(path: &str) -> std::result::Result<(&str, String), ValidationError> {
+    match path.rfind('/') {
+        Some(position) if path.starts_with('/') => Ok((
+            &path[..position],
+            path[position + 1..].replace("~1", "/").replace("~0", "~"),
+        )),
+        _ => Err(error(
+            path,
+            "JSON Pointer must start with '/'",
+            "json_patch.invalid_pointer",
+        )),
+    }
+}
+
+/// Array indices must not have leading zeros
+fn parse_index(token: &str) -> Option<usize> {
+    let is_canonical = token == "0" || !token.starts_with('0');
+    let is_numeric = !token.is_empty() && token.bytes().all(|x| x.is_ascii_digit());
+    (is_canonical && is_numeric).then(|| token.parse().ok())?
+}
+
+fn pointer_field(path: &str) -> FieldPath {
+    FieldPath::from_json_pointer(path).unwrap_or_default()
+}
+
+fn error(path: &str, reason: &str, code: &'static str) -> ValidationError {
+    ValidationError::on_field(pointer_field(path), reason)
+        .with_code(code)
+        .with_param("pointer", path)
+}
+
+fn not_found(path: &str) -> ValidationError {
+    error(path, "path doesn't exist", "json_patch.not_found")
+}
+
+fn invalid_index(path: &str) -> ValidationError {
+    error(path, "invalid array index", "json_patch.invalid_index")
+}
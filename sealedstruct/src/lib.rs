@@ -1,17 +1,44 @@
 #![doc = include_str!("../../README.md")]
 
+pub mod checks;
+pub mod diff;
+mod display;
+pub mod env;
+#[cfg(feature = "json-patch")]
+pub mod json_patch;
+pub mod merge;
+pub mod patch;
+mod path;
+#[cfg(feature = "problem-details")]
+pub mod problem_details;
+mod seal_error;
+#[cfg(feature = "serde")]
+mod serialization;
 mod stdimpl;
+mod tree;
 mod wrapper;
 
 use smallvec::SmallVec;
-use std::{collections::HashMap, fmt::Write, num, sync::Arc};
+use std::{borrow::Cow, fmt::Display, num, sync::Arc};
 
 pub type Result<T> = std::result::Result<T, ValidationErrors>;
-pub use sealedstruct_derive::{IntoNested, Nested, Seal, TryIntoNested};
+pub use diff::{ChangeKind, FieldChange};
+pub use display::{DisplayWith, Format};
+pub use merge::Merge;
+pub use patch::Patchable;
+pub use path::{FieldPath, PathSegment};
+pub use seal_error::{SealError, SealResult, SealResultExtensions};
+pub use sealedstruct_derive::{sealed, IntoNested, Nested, Seal, TryIntoNested};
+#[cfg(feature = "serde")]
+pub use serialization::by_field;
+pub use tree::ErrorTree;
 pub use wrapper::*;
 
 pub mod prelude {
-    pub use crate::{Sealable, ValidationResultExtensions};
+    pub use crate::{
+        Checkable, Merge, Patchable, SealResultExtensions, Sealable, SealedExtensions, TrySealable,
+        ValidationResultExtensions,
+    };
 }
 
 /// Usually, converting from Sealed to Raw is straight forward:
@@ -24,57 +51,98 @@ pub mod prelude {
 /// Custom types derived from `Nested` usually implement TryIntoNested only.
 /// Sealable is automatically implemented because `Seal`
 /// generates PartialEq<Sealed> for Raw and From<Sealed> for Raw
-pub trait Sealable {
+pub trait Sealable: Sized {
     type Target;
     fn seal(self) -> Result<Self::Target>;
     fn open(sealed: Self::Target) -> Self;
     // Necessary to compare without cloning
     fn partial_eq(&self, other: &Self::Target) -> bool;
+    /// Fields, which differ between the raw and the sealed value
+    ///
+    /// Types without fields, e.g. primitives, report a single change of the value itself.
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        diff::changed_unless(self.partial_eq(other))
+    }
+}
+
+/// Like [Sealable::seal], but returns the untouched raw value along with the errors
+///
+/// Implemented for the std types and by the derives. `derive(TryIntoNested)` requires it of all fields.
+pub trait TrySealable: Sealable {
+    fn try_seal(self) -> SealResult<Self>;
+}
+
+/// Reports the same errors as [Sealable::seal] without consuming the value, e.g. to validate a form on every edit
+///
+/// Implemented like [TrySealable]: For the std types, and by the derives, which require it of all fields.
+pub trait Checkable: Sealable {
+    fn check_sealable(&self) -> Result<()>;
+}
+
+/// Reverse of [Sealable]: Points from a sealed type back to the raw type it is sealed from
+///
+/// Used by `#[sealedstruct::sealed]` to declare the raw type of a field by its sealed type.
+/// Derived by `Seal`, `Nested` and `IntoNested`, where the latter is its own raw type.
+pub trait Sealed: Sized {
+    type Raw: Sealable<Target = Self>;
+}
+
+/// Changes a sealed value through its raw type and seals it again
+///
+/// Implemented for all sealed types with `Clone`, as the value is opened from a clone.
+pub trait SealedExtensions: Sealed {
+    /// On error, `self` is left untouched
+    fn try_update(&mut self, f: impl FnOnce(&mut Self::Raw)) -> Result<()>;
+    /// Like [SealedExtensions::try_update], but returns the original value along with the errors
+    fn modify(
+        self,
+        f: impl FnOnce(&mut Self::Raw),
+    ) -> std::result::Result<Self, (Self, ValidationErrors)>;
+}
+
+impl<T: Sealed + Clone> SealedExtensions for T {
+    fn try_update(&mut self, f: impl FnOnce(&mut Self::Raw)) -> Result<()> {
+        let mut raw = T::Raw::open(self.clone());
+        f(&mut raw);
+        *self = raw.seal()?;
+        Ok(())
+    }
+
+    fn modify(
+        mut self,
+        f: impl FnOnce(&mut Self::Raw),
+    ) -> std::result::Result<Self, (Self, ValidationErrors)> {
+        match self.try_update(f) {
+            Ok(()) => Ok(self),
+            Err(errors) => Err((self, errors)),
+        }
+    }
 }
 
 pub trait Validator {
     fn check(&self) -> Result<()>;
 }
 
-pub trait TryIntoNested {
+pub trait TryIntoNested: Sized {
     type Target;
     fn try_into_nested(self) -> Result<Self::Target>;
+    /// Cross-field rules, derived from `#[sealed(check = ...)]`
+    ///
+    /// They receive the raw value, so [Checkable::check_sealable] runs them without sealing.
+    /// Their errors are only reported, if all fields could be sealed.
+    fn check_rules(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(Debug, PartialEq, Default, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, Default, thiserror::Error)]
 pub struct ValidationErrors(SmallVec<[ValidationError; 1]>);
 
 // Format is used for summary-purpose only and doesn't output real JSON by choice.
+// JSON is available with the `serde` feature.
 impl std::fmt::Display for ValidationErrors {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("ValidationErrors({}): {}", self.0.len(), '{'))?;
-        let mut map: HashMap<&str, SmallVec<[&str; 1]>> = HashMap::new();
-
-        for error in self.0.iter() {
-            for field in error.iter_fields() {
-                match map.entry(field) {
-                    std::collections::hash_map::Entry::Occupied(mut e) => {
-                        e.get_mut().push(error.reason.as_str());
-                    }
-                    std::collections::hash_map::Entry::Vacant(e) => {
-                        e.insert(SmallVec::from_const([error.reason.as_str()]));
-                    }
-                }
-            }
-        }
-
-        let mut iter = map.into_iter();
-        if let Some((first, first_reasons)) = iter.next() {
-            f.write_fmt(format_args!("{}: {:?}", first, first_reasons))?;
-            for (x, x_reasons) in iter.by_ref().take(4) {
-                f.write_fmt(format_args!(", {}: {:?}", x, x_reasons))?;
-            }
-            if iter.next().is_some() {
-                f.write_str(", ...")?;
-            }
-        }
-
-        f.write_char('}')
+        self.display_with(Format::Compact).fmt(f)
     }
 }
 
@@ -83,7 +151,7 @@ impl ValidationErrors {
         Self(SmallVec::from_const([error]))
     }
     pub fn combine_with(mut self, other: ValidationErrors) -> Self {
-        self.0.extend(other.0.into_iter());
+        self.0.extend(other.0);
         self
     }
 
@@ -108,42 +176,89 @@ impl IntoIterator for ValidationErrors {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ValidationError {
-    fields: SmallVec<[String; 1]>,
+    fields: SmallVec<[FieldPath; 1]>,
     pub reason: String,
+    // Boxed to keep Result small, as most errors have neither code nor params
+    details: Option<Box<ErrorDetails>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct ErrorDetails {
+    code: Option<Cow<'static, str>>,
+    params: Vec<(Cow<'static, str>, String)>,
 }
 
 impl ValidationError {
+    /// Code of errors, which didn't set one with [ValidationError::with_code]
+    pub const DEFAULT_CODE: &'static str = "invalid";
+
     pub fn new(reason: impl Into<String>) -> Self {
-        Self {
-            fields: SmallVec::from_const(["".into()]),
-            reason: reason.into(),
-        }
+        Self::with_fields(SmallVec::from_const([FieldPath::root()]), reason.into())
     }
 
-    pub fn on_field(field: impl Into<String>, reason: impl Into<String>) -> Self {
-        Self {
-            fields: SmallVec::from_const([field.into()]),
-            reason: reason.into(),
-        }
+    /// Strings are parsed in the dotted notation, e.g. `"items[3].name"`
+    pub fn on_field(field: impl Into<FieldPath>, reason: impl Into<String>) -> Self {
+        Self::with_fields(SmallVec::from_const([field.into()]), reason.into())
     }
 
-    pub fn on_fields<TField: Into<String>>(
+    pub fn on_fields<TField: Into<FieldPath>>(
         first: TField,
         rest: impl IntoIterator<Item = TField>,
         reason: impl Into<String>,
     ) -> Self {
-        let mut r = Self {
-            fields: SmallVec::from_const([first.into()]),
-            reason: reason.into(),
-        };
+        let mut r = Self::with_fields(SmallVec::from_const([first.into()]), reason.into());
         r.fields.extend(rest.into_iter().map(|x| x.into()));
         r
     }
 
-    pub fn iter_fields(&self) -> impl Iterator<Item = &str> {
-        self.fields.iter().map(String::as_ref)
+    fn with_fields(fields: SmallVec<[FieldPath; 1]>, reason: String) -> Self {
+        Self {
+            fields,
+            reason,
+            details: None,
+        }
+    }
+
+    /// Sets a machine-readable code like `"range.max"`, so clients don't have to match the reason
+    pub fn with_code(mut self, code: impl Into<Cow<'static, str>>) -> Self {
+        self.details.get_or_insert_with(Default::default).code = Some(code.into());
+        self
+    }
+
+    /// Adds a parameter of the reason, e.g. `max = 100`. Values are formatted with Display.
+    pub fn with_param(mut self, name: impl Into<Cow<'static, str>>, value: impl Display) -> Self {
+        self.details
+            .get_or_insert_with(Default::default)
+            .params
+            .push((name.into(), value.to_string()));
+        self
+    }
+
+    pub fn iter_fields(&self) -> impl Iterator<Item = &FieldPath> {
+        self.fields.iter()
+    }
+
+    pub fn code(&self) -> &str {
+        self.details
+            .as_ref()
+            .and_then(|details| details.code.as_deref())
+            .unwrap_or(Self::DEFAULT_CODE)
+    }
+
+    /// Parameters in the order they were added
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.details
+            .iter()
+            .flat_map(|details| details.params.iter())
+            .map(|(name, value)| (name.as_ref(), value.as_str()))
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params()
+            .find(|(candidate, _)| *candidate == name)
+            .map(|(_, value)| value)
     }
 }
 
@@ -156,7 +271,15 @@ impl<T> From<ValidationError> for Result<T> {
 pub trait ValidationResultExtensions {
     type Ok;
     fn combine<T>(self, other: Result<T>) -> Result<(Self::Ok, T)>;
+    /// Parses `path` in the dotted notation, e.g. `"items[3]"`
     fn prepend_path(self, path: &str) -> Self;
+    fn prepend(self, segment: PathSegment) -> Self;
+    fn prepend_field(self, name: &'static str) -> Self;
+    fn prepend_variant(self, name: &'static str) -> Self;
+    /// Prepends the position of an element in a list, e.g. `[3]` to get `items[3].name`
+    fn prepend_index(self, index: usize) -> Self;
+    /// Prepends the key of a map entry formatted with Debug, e.g. `["a"]` to get `map["a"].name`
+    fn prepend_key(self, key: &impl std::fmt::Debug) -> Self;
     fn append_error(self, error: ValidationError) -> Self;
 }
 
@@ -173,23 +296,47 @@ impl<TOwn> ValidationResultExtensions for Result<TOwn> {
     }
 
     fn prepend_path(self, path: &str) -> Self {
+        self.map_err(|mut errors| {
+            let path = FieldPath::parse(path);
+            for error in errors.0.iter_mut() {
+                for field in error.fields.iter_mut() {
+                    field.prepend_path(path.clone());
+                }
+            }
+            errors
+        })
+    }
+
+    fn prepend(self, segment: PathSegment) -> Self {
         self.map_err(|mut errors| {
             for error in errors.0.iter_mut() {
                 for field in error.fields.iter_mut() {
-                    if !field.is_empty() {
-                        field.reserve(path.len() + 1);
-                        field.insert(0, '.');
-                    } else {
-                        field.reserve(path.len());
-                    }
-
-                    field.insert_str(0, path);
+                    field.prepend(segment.clone());
                 }
             }
             errors
         })
     }
 
+    fn prepend_field(self, name: &'static str) -> Self {
+        self.prepend(PathSegment::Field(name.into()))
+    }
+
+    fn prepend_variant(self, name: &'static str) -> Self {
+        self.prepend(PathSegment::Variant(name.into()))
+    }
+
+    fn prepend_index(self, index: usize) -> Self {
+        self.prepend(PathSegment::Index(index))
+    }
+
+    fn prepend_key(self, key: &impl std::fmt::Debug) -> Self {
+        match self {
+            Ok(x) => Ok(x),
+            e => e.prepend(PathSegment::key(key)),
+        }
+    }
+
     fn append_error(self, error: ValidationError) -> Self {
         match self {
             Ok(_) => error.into(),
@@ -216,6 +363,25 @@ macro_rules! sealed_to_self {
                     self.eq(other)
                 }
             }
+
+            impl TrySealable for $type {
+                fn try_seal(self) -> SealResult<Self> {
+                    Ok(self)
+                }
+            }
+
+            impl Checkable for $type {
+                fn check_sealable(&self) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            impl Sealed for $type {
+                type Raw = Self;
+            }
+
+            $crate::patch::replace_on_patch! { [] $type }
+            $crate::merge::replace_on_merge! { [] $type }
         )*
     };
 }
@@ -235,7 +401,7 @@ impl<T0: Sealable, T1: Sealable> Sealable for (T0, T1) {
     type Target = (T0::Target, T1::Target);
 
     fn seal(self) -> Result<Self::Target> {
-        Ok((self.0.seal()?, self.1.seal()?))
+        self.0.seal().combine(self.1.seal())
     }
 
     fn open(sealed: Self::Target) -> Self {
@@ -245,12 +411,41 @@ impl<T0: Sealable, T1: Sealable> Sealable for (T0, T1) {
     fn partial_eq(&self, other: &Self::Target) -> bool {
         self.0.partial_eq(&other.0) && self.1.partial_eq(&other.1)
     }
+
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        let mut changes = self.0.diff(&other.0);
+        changes.extend(self.1.diff(&other.1));
+        changes
+    }
+}
+impl<T0: TrySealable, T1: TrySealable> TrySealable for (T0, T1) {
+    fn try_seal(self) -> SealResult<Self> {
+        self.0.try_seal().combine(self.1.try_seal())
+    }
+}
+
+impl<T0: Checkable, T1: Checkable> Checkable for (T0, T1) {
+    fn check_sealable(&self) -> Result<()> {
+        self.0
+            .check_sealable()
+            .combine(self.1.check_sealable())
+            .map(|_| ())
+    }
 }
+
+impl<T0: Sealed, T1: Sealed> Sealed for (T0, T1) {
+    type Raw = (T0::Raw, T1::Raw);
+}
+
 impl<T0: Sealable, T1: Sealable, T2: Sealable> Sealable for (T0, T1, T2) {
     type Target = (T0::Target, T1::Target, T2::Target);
 
     fn seal(self) -> Result<Self::Target> {
-        Ok((self.0.seal()?, self.1.seal()?, self.2.seal()?))
+        self.0
+            .seal()
+            .combine(self.1.seal())
+            .combine(self.2.seal())
+            .map(|((a, b), c)| (a, b, c))
     }
 
     fn open(sealed: Self::Target) -> Self {
@@ -260,6 +455,38 @@ impl<T0: Sealable, T1: Sealable, T2: Sealable> Sealable for (T0, T1, T2) {
     fn partial_eq(&self, other: &Self::Target) -> bool {
         self.0.partial_eq(&other.0) && self.1.partial_eq(&other.1) && self.2.partial_eq(&other.2)
     }
+
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        let mut changes = self.0.diff(&other.0);
+        changes.extend(self.1.diff(&other.1));
+        changes.extend(self.2.diff(&other.2));
+        changes
+    }
+}
+
+impl<T0: TrySealable, T1: TrySealable, T2: TrySealable> TrySealable for (T0, T1, T2) {
+    fn try_seal(self) -> SealResult<Self> {
+        self.0
+            .try_seal()
+            .combine(self.1.try_seal())
+            .combine(self.2.try_seal())
+            .map(|((a, b), c)| (a, b, c))
+            .map_err(|e| e.map_raw(|((a, b), c)| (a, b, c)))
+    }
+}
+
+impl<T0: Checkable, T1: Checkable, T2: Checkable> Checkable for (T0, T1, T2) {
+    fn check_sealable(&self) -> Result<()> {
+        self.0
+            .check_sealable()
+            .combine(self.1.check_sealable())
+            .combine(self.2.check_sealable())
+            .map(|_| ())
+    }
+}
+
+impl<T0: Sealed, T1: Sealed, T2: Sealed> Sealed for (T0, T1, T2) {
+    type Raw = (T0::Raw, T1::Raw, T2::Raw);
 }
 
 impl<T: Sealable + Clone> Sealable for Arc<T>
@@ -279,6 +506,38 @@ where
     fn partial_eq(&self, other: &Self::Target) -> bool {
         <T as Sealable>::partial_eq(self, other)
     }
+
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        <T as Sealable>::diff(self, other)
+    }
+}
+
+impl<T: TrySealable + Clone> TrySealable for Arc<T>
+where
+    T::Target: Clone,
+{
+    fn try_seal(self) -> SealResult<Self> {
+        match T::clone(&self).try_seal() {
+            Ok(sealed) => Ok(Arc::new(sealed)),
+            Err(e) => Err(SealError::new(e.errors, self)),
+        }
+    }
+}
+
+impl<T: Checkable + Clone> Checkable for Arc<T>
+where
+    T::Target: Clone,
+{
+    fn check_sealable(&self) -> Result<()> {
+        <T as Checkable>::check_sealable(self)
+    }
+}
+
+impl<T: Sealed + Clone> Sealed for Arc<T>
+where
+    T::Raw: Clone,
+{
+    type Raw = Arc<T::Raw>;
 }
 
 mod std_derives {
@@ -309,7 +568,7 @@ mod chrono_derives {
 
 #[cfg(test)]
 mod tests {
-    use crate::{ValidationError, ValidationErrors};
+    use crate::{FieldPath, ValidationError, ValidationErrors};
 
     use super::prelude::*;
 
@@ -325,7 +584,8 @@ mod tests {
                 .next()
                 .expect("OneError")
                 .iter_fields()
-                .collect::<Vec<&str>>()
+                .map(FieldPath::to_string)
+                .collect::<Vec<_>>()
         );
         assert_eq!(errors.next(), None);
     }
@@ -343,7 +603,8 @@ mod tests {
                 .next()
                 .expect("OneError")
                 .iter_fields()
-                .collect::<Vec<&str>>()
+                .map(FieldPath::to_string)
+                .collect::<Vec<_>>()
         );
         assert_eq!(
             vec!["Bar"],
@@ -351,7 +612,8 @@ mod tests {
                 .next()
                 .expect("OneError")
                 .iter_fields()
-                .collect::<Vec<&str>>()
+                .map(FieldPath::to_string)
+                .collect::<Vec<_>>()
         );
     }
 
@@ -363,6 +625,28 @@ mod tests {
         assert_eq!(2, combined.into_iter().count());
     }
 
+    #[test]
+    fn default_code() {
+        let error = ValidationError::on_field("Foo", "FooError");
+        assert_eq!(ValidationError::DEFAULT_CODE, error.code());
+        assert_eq!(None, error.params().next());
+    }
+
+    #[test]
+    fn code_and_params() {
+        let error = ValidationError::new("must be at most 100")
+            .with_code("range.max")
+            .with_param("max", 100)
+            .with_param("actual", 127);
+        assert_eq!("range.max", error.code());
+        assert_eq!(
+            vec![("max", "100"), ("actual", "127")],
+            error.params().collect::<Vec<_>>()
+        );
+        assert_eq!(Some("127"), error.param("actual"));
+        assert_eq!(None, error.param("min"));
+    }
+
     #[test]
     fn format_validation_error() {
         let result: super::Result<()> = ValidationError::on_field("Foo", "CustomMessage").into();
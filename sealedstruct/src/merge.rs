@@ -0,0 +1,264 @@
+//! Merging several partial raw values in precedence order, e.g. defaults, a file and environment overrides
+//!
+//! ```
+//! # #[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+//! # #[sealed(merge)]
+//! # pub struct ConfigRaw {
+//! #     name: Option<String>,
+//! #     #[sealed(merge = "append")]
+//! #     hosts: Vec<String>,
+//! # }
+//! use sealedstruct::merge::Layers;
+//!
+//! let defaults = ConfigRaw { name: Some("service".into()), hosts: vec!["a".into()] };
+//! let file = ConfigRaw { name: None, hosts: vec!["b".into()] };
+//! let config = Layers::new("defaults", defaults).layer("file", file).seal().unwrap();
+//! assert_eq!(Some("service".to_string()), config.name);
+//! assert_eq!(vec!["a", "b"], config.hosts);
+//! ```
+//!
+//! Fields, which a layer may leave unset, should be optional: `None` keeps the value of the previous layers,
+//! while `Some` is merged into it. All other values replace it, unless the field has another strategy.
+
+use crate::{FieldPath, PathSegment, Result, Sealable, ValidationErrors};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::Arc,
+};
+
+/// Selected for a field with `#[sealed(merge = "replace" | "append" | "deep")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// The other value replaces the whole value
+    Replace,
+    /// Items of lists and sets are appended, entries of maps are inserted
+    Append,
+    /// Values at the same position, key or field are merged recursively
+    Deep,
+}
+
+/// Raw types, which can be merged with a value of higher precedence
+pub trait Merge: Sized {
+    /// Merges `other` into `self` with the default strategy of the type
+    ///
+    /// Structs are merged field by field, maps by their keys, and `None` keeps `self`, while two `Some` are merged.
+    /// All other values are replaced.
+    fn merge(&mut self, other: Self);
+
+    /// Types without a matching strategy, e.g. primitives, are merged with their default strategy
+    fn merge_with(&mut self, other: Self, strategy: Strategy) {
+        match strategy {
+            Strategy::Replace => *self = other,
+            Strategy::Append | Strategy::Deep => self.merge(other),
+        }
+    }
+
+    /// Fields, which this value supplies when it is merged into another one. Used to tag errors with their layer.
+    fn supplied(&self) -> Vec<FieldPath> {
+        vec![FieldPath::root()]
+    }
+}
+
+/// Implements [Merge] by replacing the whole value
+macro_rules! replace_on_merge {
+    ($([$($generics:tt)*] $type:ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> $crate::merge::Merge for $type {
+                fn merge(&mut self, other: Self) {
+                    *self = other;
+                }
+            }
+        )*
+    };
+}
+pub(crate) use replace_on_merge;
+
+replace_on_merge! {
+    [T] Arc<T>,
+    [T0, T1] (T0, T1),
+    [T0, T1, T2] (T0, T1, T2),
+    [T] crate::IntoNestedWrapper<T>,
+}
+
+impl<T: Merge> Merge for Option<T> {
+    fn merge(&mut self, other: Self) {
+        match (self.as_mut(), other) {
+            (_, None) => {}
+            (Some(value), Some(other)) => value.merge(other),
+            (_, other) => *self = other,
+        }
+    }
+
+    /// If both are `Some`, their values are merged with the strategy
+    fn merge_with(&mut self, other: Self, strategy: Strategy) {
+        match (self.as_mut(), other) {
+            (_, None) => {}
+            (Some(value), Some(other)) if strategy != Strategy::Replace => {
+                value.merge_with(other, strategy)
+            }
+            (_, other) => *self = other,
+        }
+    }
+
+    fn supplied(&self) -> Vec<FieldPath> {
+        match self {
+            Some(value) => value.supplied(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<T: Merge> Merge for Vec<T> {
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+
+    /// `Deep` merges the items at the same index and appends the remaining ones
+    fn merge_with(&mut self, other: Self, strategy: Strategy) {
+        match strategy {
+            Strategy::Replace => *self = other,
+            Strategy::Append => self.extend(other),
+            Strategy::Deep => {
+                let mut other = other.into_iter();
+                for (item, other) in self.iter_mut().zip(other.by_ref()) {
+                    item.merge_with(other, Strategy::Deep);
+                }
+                self.extend(other);
+            }
+        }
+    }
+}
+
+impl<T: Eq + Hash> Merge for HashSet<T> {
+    fn merge(&mut self, other: Self) {
+        *self = other;
+    }
+
+    fn merge_with(&mut self, other: Self, strategy: Strategy) {
+        match strategy {
+            Strategy::Replace => *self = other,
+            Strategy::Append | Strategy::Deep => self.extend(other),
+        }
+    }
+}
+
+impl<TKey, TValue> Merge for HashMap<TKey, TValue>
+where
+    TKey: Eq + Hash + std::fmt::Debug,
+    TValue: Merge,
+{
+    fn merge(&mut self, other: Self) {
+        self.merge_with(other, Strategy::Append);
+    }
+
+    /// `Append` replaces the values of existing keys, while `Deep` merges them
+    fn merge_with(&mut self, other: Self, strategy: Strategy) {
+        match strategy {
+            Strategy::Replace => *self = other,
+            Strategy::Append => self.extend(other),
+            Strategy::Deep => {
+                for (key, other) in other {
+                    match self.get_mut(&key) {
+                        Some(value) => value.merge_with(other, Strategy::Deep),
+                        None => {
+                            self.insert(key, other);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn supplied(&self) -> Vec<FieldPath> {
+        self.keys()
+            .map(|key| PathSegment::key(key).into())
+            .collect()
+    }
+}
+
+/// Prepends the segment to all supplied fields. Used by the derived implementations.
+pub fn prepend(fields: Vec<FieldPath>, segment: PathSegment) -> Vec<FieldPath> {
+    fields
+        .into_iter()
+        .map(|mut field| {
+            field.prepend(segment.clone());
+            field
+        })
+        .collect()
+}
+
+pub fn prepend_field(fields: Vec<FieldPath>, name: &'static str) -> Vec<FieldPath> {
+    prepend(fields, PathSegment::Field(name.into()))
+}
+
+/// Merges named layers in precedence order, so errors of sealing can be tagged with the layer of their field
+pub struct Layers<T> {
+    merged: T,
+    supplied: Vec<(Cow<'static, str>, Vec<FieldPath>)>,
+}
+
+impl<T: Merge> Layers<T> {
+    pub fn new(name: impl Into<Cow<'static, str>>, raw: T) -> Self {
+        Self {
+            supplied: vec![(name.into(), raw.supplied())],
+            merged: raw,
+        }
+    }
+
+    /// Adds a layer, which takes precedence over all previous ones
+    pub fn layer(mut self, name: impl Into<Cow<'static, str>>, raw: T) -> Self {
+        self.supplied.push((name.into(), raw.supplied()));
+        self.merged.merge(raw);
+        self
+    }
+}
+
+impl<T> Layers<T> {
+    pub fn into_raw(self) -> T {
+        self.merged
+    }
+
+    /// Name of the last layer, which supplied a value containing the field
+    pub fn layer_of(&self, field: &FieldPath) -> Option<&str> {
+        self.supplied
+            .iter()
+            .rev()
+            .find(|(_, supplied)| {
+                supplied
+                    .iter()
+                    .any(|prefix| field.strip_prefix(prefix).is_some())
+            })
+            .map(|(name, _)| name.as_ref())
+    }
+}
+
+impl<T: Merge + Sealable> Layers<T> {
+    /// Seals the merged value. Errors get the param `layer`, if a layer supplied one of their fields.
+    pub fn seal(self) -> Result<T::Target> {
+        let Self { merged, supplied } = self;
+        // The merged value is moved out to seal it, only the supplied fields are kept
+        let layers = Layers {
+            merged: (),
+            supplied,
+        };
+        merged.seal().map_err(|errors| {
+            ValidationErrors(
+                errors
+                    .into_iter()
+                    .map(|error| {
+                        let layer = error
+                            .iter_fields()
+                            .find_map(|field| layers.layer_of(field))
+                            .map(str::to_string);
+                        match layer {
+                            Some(layer) => error.with_param("layer", layer),
+                            None => error,
+                        }
+                    })
+                    .collect(),
+            )
+        })
+    }
+}
@@ -0,0 +1,56 @@
+//! Partial updates of raw values, e.g. for PATCH endpoints
+//!
+//! `#[sealed(patch)]` on a `Nested` struct generates `{Structname}Patch`, where every field is optional,
+//! and `{Structname}::apply_patch`. All other types are replaced as a whole.
+
+use crate::IntoNestedWrapper;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Raw types, which can be changed by a patch
+pub trait Patchable {
+    type Patch;
+    fn patch(&mut self, patch: Self::Patch);
+}
+
+/// Implements [Patchable] by replacing the whole value
+macro_rules! replace_on_patch {
+    ($([$($generics:tt)*] $type:ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> $crate::Patchable for $type {
+                type Patch = Self;
+
+                fn patch(&mut self, patch: Self) {
+                    *self = patch;
+                }
+            }
+        )*
+    };
+}
+pub(crate) use replace_on_patch;
+
+replace_on_patch! {
+    [T] Vec<T>,
+    [T] Option<T>,
+    [T] Arc<T>,
+    [T] HashSet<T>,
+    [TKey, TValue] HashMap<TKey, TValue>,
+    [T0, T1] (T0, T1),
+    [T0, T1, T2] (T0, T1, T2),
+    [T] IntoNestedWrapper<T>,
+}
+
+/// Deserializes a present field as `Some`, even if it is `null`
+///
+/// Used for the fields of generated patches, so `null` clears an optional field instead of keeping it.
+/// Missing fields are `None` by `#[serde(default)]`.
+#[cfg(feature = "serde")]
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,340 @@
+//! Structured location of an invalid value within the validated type
+
+use std::{borrow::Cow, fmt};
+
+/// Single step of a [FieldPath]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Named or positional field of a struct
+    Field(Cow<'static, str>),
+    /// Position in a list
+    Index(usize),
+    /// Key of a map
+    Key(String),
+    /// Variant of an enum
+    Variant(Cow<'static, str>),
+}
+
+impl PathSegment {
+    /// Uses the Debug representation of a map key. Quotes of string keys are removed.
+    pub fn key(key: &impl fmt::Debug) -> Self {
+        let debug = format!("{key:?}");
+        match parse_quoted(&debug) {
+            Some((key, "")) => Self::Key(key),
+            _ => Self::Key(debug),
+        }
+    }
+
+    /// Fields and variants render the same, so they match if their names are equal.
+    /// This allows to look up derived paths with parsed ones.
+    pub(crate) fn matches(&self, other: &PathSegment) -> bool {
+        match (self, other) {
+            (
+                PathSegment::Field(a) | PathSegment::Variant(a),
+                PathSegment::Field(b) | PathSegment::Variant(b),
+            ) => a == b,
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// Location of an invalid value, e.g. `items[3].name`
+///
+/// The root path has no segments and refers to the validated value itself.
+/// [Display](fmt::Display) renders the dotted notation, which can be parsed again with [FieldPath::parse].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FieldPath(Vec<PathSegment>);
+
+impl FieldPath {
+    pub const fn root() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    pub fn prepend(&mut self, segment: PathSegment) {
+        self.0.insert(0, segment);
+    }
+
+    pub fn prepend_path(&mut self, path: FieldPath) {
+        self.0.splice(0..0, path.0);
+    }
+
+    /// Returns the remaining path, if `self` is within `prefix`. Fields and variants of the same name match.
+    pub fn strip_prefix(&self, prefix: &FieldPath) -> Option<FieldPath> {
+        let rest = self.0.get(prefix.0.len()..)?;
+        let is_within = self
+            .0
+            .iter()
+            .zip(prefix.0.iter())
+            .all(|(a, b)| a.matches(b));
+        is_within.then(|| Self(rest.to_vec()))
+    }
+
+    /// Parses the dotted notation, e.g. `items[3].tags["a.b"]`
+    ///
+    /// Numbers in brackets are indices, everything else in brackets is a key.
+    /// Keys containing `.`, `[` or `]` have to be quoted like a Rust string.
+    pub fn parse(path: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = path;
+        while !rest.is_empty() {
+            if let Some(bracket) = rest.strip_prefix('[') {
+                if let Some((key, after)) = parse_quoted(bracket) {
+                    if let Some(after) = after.strip_prefix(']') {
+                        segments.push(PathSegment::Key(key));
+                        rest = after;
+                        continue;
+                    }
+                }
+                if let Some(end) = bracket.find(']') {
+                    let content = &bracket[..end];
+                    segments.push(match content.parse() {
+                        Ok(index) => PathSegment::Index(index),
+                        Err(_) => PathSegment::Key(content.to_string()),
+                    });
+                    rest = &bracket[end + 1..];
+                    continue;
+                }
+                // Unclosed brackets are kept as part of the field name
+            }
+            let field = rest.strip_prefix('.').unwrap_or(rest);
+            // Searching starts after the first char, so an unclosed bracket is part of the name
+            let first = field.chars().next().map_or(0, char::len_utf8);
+            let end = field[first..]
+                .find(['.', '['])
+                .map_or(field.len(), |end| end + first);
+            if end > 0 {
+                segments.push(PathSegment::Field(field[..end].to_string().into()));
+            }
+            rest = &field[end..];
+        }
+        Self(segments)
+    }
+
+    /// Renders a JSON Pointer according to RFC 6901, e.g. `/items/3/name`
+    pub fn to_json_pointer(&self) -> String {
+        let mut result = String::new();
+        for segment in self.0.iter() {
+            result.push('/');
+            let escaped = match segment {
+                PathSegment::Field(name) | PathSegment::Variant(name) => escape_pointer(name),
+                PathSegment::Key(key) => escape_pointer(key),
+                PathSegment::Index(index) => index.to_string(),
+            };
+            result.push_str(&escaped);
+        }
+        result
+    }
+
+    /// Parses a JSON Pointer according to RFC 6901. Returns `None`, if it doesn't start with `/`.
+    ///
+    /// Numeric tokens become indices, all others fields, as pointers don't distinguish fields and keys.
+    pub fn from_json_pointer(pointer: &str) -> Option<Self> {
+        if pointer.is_empty() {
+            return Some(Self::root());
+        }
+        let segments = pointer
+            .strip_prefix('/')?
+            .split('/')
+            .map(|token| match token.parse() {
+                Ok(index) if !token.starts_with('+') => PathSegment::Index(index),
+                _ => PathSegment::Field(unescape_pointer(token).into()),
+            })
+            .collect();
+        Some(Self(segments))
+    }
+
+    /// Renders the bracket notation used by HTML forms, e.g. `items[3][name]`
+    pub fn to_bracket_notation(&self) -> String {
+        let mut result = String::new();
+        for (position, segment) in self.0.iter().enumerate() {
+            let text = match segment {
+                PathSegment::Field(name) | PathSegment::Variant(name) => name.to_string(),
+                PathSegment::Key(key) => key.clone(),
+                PathSegment::Index(index) => index.to_string(),
+            };
+            let is_name = matches!(segment, PathSegment::Field(_) | PathSegment::Variant(_));
+            if position == 0 && is_name {
+                result.push_str(&text);
+            } else {
+                result.push('[');
+                result.push_str(&text);
+                result.push(']');
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) | PathSegment::Variant(name) => {
+                    if position > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(name)?;
+                }
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Key(key) => write!(f, "[{key:?}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for FieldPath {
+    fn from(path: &str) -> Self {
+        Self::parse(path)
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(path: String) -> Self {
+        Self::parse(&path)
+    }
+}
+
+impl From<PathSegment> for FieldPath {
+    fn from(segment: PathSegment) -> Self {
+        Self(vec![segment])
+    }
+}
+
+// Allows to compare with the dotted notation, e.g. in tests
+impl PartialEq<str> for FieldPath {
+    fn eq(&self, other: &str) -> bool {
+        self.to_string().as_str() == other
+    }
+}
+
+impl PartialEq<&str> for FieldPath {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<FieldPath> for str {
+    fn eq(&self, other: &FieldPath) -> bool {
+        other == self
+    }
+}
+
+fn escape_pointer(text: &str) -> String {
+    text.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Parses a string in Rust's Debug format at the start of `text`, returning the remaining text
+fn parse_quoted(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut result = String::new();
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '"' => return Some((result, &text[position + 2..])),
+            '\\' => result.push(match chars.next()?.1 {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                '0' => '\0',
+                'u' => {
+                    let (start, _) = chars.next().filter(|(_, c)| *c == '{')?;
+                    let (end, _) = chars.by_ref().find(|(_, c)| *c == '}')?;
+                    let hex = &text[start + 2..end + 1];
+                    char::from_u32(u32::from_str_radix(hex, 16).ok()?)?
+                }
+                other => other,
+            }),
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dotted() {
+        let path = FieldPath::parse(r#"items[3].tags["a.b"].name"#);
+        assert_eq!(
+            &[
+                PathSegment::Field("items".into()),
+                PathSegment::Index(3),
+                PathSegment::Field("tags".into()),
+                PathSegment::Key("a.b".into()),
+                PathSegment::Field("name".into()),
+            ],
+            path.segments()
+        );
+        assert_eq!(r#"items[3].tags["a.b"].name"#, path.to_string());
+        assert!(FieldPath::parse("").is_root());
+    }
+
+    #[test]
+    fn unquoted_keys_are_kept() {
+        let path = FieldPath::parse("map[some-id].x");
+        assert_eq!(
+            &[
+                PathSegment::Field("map".into()),
+                PathSegment::Key("some-id".into()),
+                PathSegment::Field("x".into()),
+            ],
+            path.segments()
+        );
+    }
+
+    #[test]
+    fn keys_from_debug() {
+        assert_eq!(PathSegment::Key("a\"b".into()), PathSegment::key(&"a\"b"));
+        assert_eq!(
+            PathSegment::Key("é\u{301}".into()),
+            PathSegment::key(&"é\u{301}")
+        );
+        assert_eq!(PathSegment::Key("42".into()), PathSegment::key(&42));
+    }
+
+    #[test]
+    fn render() {
+        let mut path = FieldPath::from(PathSegment::Key("a/b~c".into()));
+        path.prepend(PathSegment::Field("map".into()));
+        path.0.push(PathSegment::Index(1));
+        assert_eq!("/map/a~1b~0c/1", path.to_json_pointer());
+        assert_eq!("map[a/b~c][1]", path.to_bracket_notation());
+        assert_eq!(r#"map["a/b~c"][1]"#, path.to_string());
+        assert_eq!("", FieldPath::root().to_json_pointer());
+
+        let parsed = FieldPath::from_json_pointer("/map/a~1b~0c/1").unwrap();
+        assert_eq!("/map/a~1b~0c/1", parsed.to_json_pointer());
+        assert_eq!(PathSegment::Index(1), parsed.segments()[2]);
+        assert!(FieldPath::from_json_pointer("").unwrap().is_root());
+        assert!(FieldPath::from_json_pointer("map").is_none());
+    }
+
+    #[test]
+    fn strip_prefix() {
+        let mut path = FieldPath::parse("int8");
+        path.prepend(PathSegment::Index(3));
+        path.prepend(PathSegment::Variant("numbers".into()));
+        assert_eq!(
+            Some(FieldPath::parse("[3].int8")),
+            path.strip_prefix(&"numbers".into())
+        );
+        assert_eq!(Some(FieldPath::root()), path.strip_prefix(&path));
+        assert_eq!(Some(path.clone()), path.strip_prefix(&FieldPath::root()));
+        assert_eq!(None, path.strip_prefix(&"numbers[4]".into()));
+        assert_eq!(None, path.strip_prefix(&"numbers[3].int8.x".into()));
+    }
+}
@@ -0,0 +1,171 @@
+//! Problem details for HTTP APIs according to RFC 7807
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Unprocessable Entity",
+//!   "status": 422,
+//!   "detail": "1 invalid parameter",
+//!   "invalid-params": [{ "name": "numbers[3].int8", "reason": "must be <100", "code": "invalid" }]
+//! }
+//! ```
+
+use crate::{FieldPath, ValidationError, ValidationErrors};
+use serde::{Deserialize, Serialize};
+
+/// Body of an `application/problem+json` response
+///
+/// All fields are public, so `type` and `title` can be replaced by a more specific problem type.
+/// Like all members of RFC 7807, `title` and `status` may be missing in parsed bodies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type", default = "about_blank")]
+    pub type_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(rename = "invalid-params", default)]
+    pub invalid_params: Vec<InvalidParam>,
+}
+
+/// Entry of the `invalid-params` extension
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvalidParam {
+    /// Path in the dotted notation of [FieldPath]
+    pub name: String,
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl ProblemDetails {
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+    pub const STATUS: u16 = 422;
+}
+
+fn about_blank() -> String {
+    "about:blank".into()
+}
+
+impl ValidationErrors {
+    /// Creates an `invalid-params` entry for each field of each error
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        let invalid_params = self
+            .iter()
+            .flat_map(|error| {
+                error.iter_fields().map(|field| InvalidParam {
+                    name: field.to_string(),
+                    reason: error.reason.clone(),
+                    code: Some(error.code().to_string()),
+                })
+            })
+            .collect::<Vec<_>>();
+        let detail = match invalid_params.len() {
+            1 => "1 invalid parameter".to_string(),
+            n => format!("{n} invalid parameters"),
+        };
+        ProblemDetails {
+            type_uri: about_blank(),
+            title: Some("Unprocessable Entity".into()),
+            status: Some(ProblemDetails::STATUS),
+            detail: Some(detail),
+            invalid_params,
+        }
+    }
+}
+
+/// Parses the `invalid-params` back into errors. Errors on multiple fields are split up
+/// into one error per field, as problem details don't group them. Params are not preserved.
+impl From<ProblemDetails> for ValidationErrors {
+    fn from(problem: ProblemDetails) -> Self {
+        ValidationErrors(
+            problem
+                .invalid_params
+                .into_iter()
+                .map(|param| {
+                    let error =
+                        ValidationError::on_field(FieldPath::parse(&param.name), param.reason);
+                    match param.code {
+                        Some(code) if code != ValidationError::DEFAULT_CODE => {
+                            error.with_code(code)
+                        }
+                        _ => error,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Result, ValidationResultExtensions};
+
+    #[test]
+    fn roundtrip() {
+        let first: Result<()> = ValidationError::new("must be at most 100, got 127")
+            .with_code("range.max")
+            .into();
+        let second: Result<()> = ValidationError::on_field("name", "must not be empty").into();
+        let errors = first
+            .prepend_path("int8")
+            .prepend_index(3)
+            .prepend_path("numbers")
+            .combine(second)
+            .unwrap_err();
+
+        let json = serde_json::to_value(errors.to_problem_details()).unwrap();
+        assert_eq!(
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Unprocessable Entity",
+                "status": 422,
+                "detail": "2 invalid parameters",
+                "invalid-params": [
+                    { "name": "numbers[3].int8", "reason": "must be at most 100, got 127", "code": "range.max" },
+                    { "name": "name", "reason": "must not be empty", "code": "invalid" }
+                ]
+            }),
+            json
+        );
+
+        let problem: ProblemDetails = serde_json::from_value(json).unwrap();
+        assert_eq!(errors, ValidationErrors::from(problem));
+    }
+
+    #[test]
+    fn parse_minimal() {
+        let problem: ProblemDetails = serde_json::from_str(
+            r#"{"title": "Invalid", "status": 400, "invalid-params": [{"name": "age", "reason": "must be positive"}]}"#,
+        )
+        .unwrap();
+        assert_eq!("about:blank", problem.type_uri);
+
+        let errors = ValidationErrors::from(problem);
+        let error = errors.iter().next().unwrap();
+        assert_eq!(vec!["age"], error.iter_fields().collect::<Vec<_>>());
+        assert_eq!(ValidationError::DEFAULT_CODE, error.code());
+    }
+
+    #[test]
+    fn parse_only_invalid_params() {
+        let problem: ProblemDetails = serde_json::from_str(
+            r#"{"invalid-params": [{"name": "numbers[3].int8", "reason": "must be <100"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(None, problem.title);
+        assert_eq!(None, problem.status);
+
+        let errors = ValidationErrors::from(problem);
+        let fields = errors
+            .iter()
+            .flat_map(ValidationError::iter_fields)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["numbers[3].int8"], fields);
+    }
+}
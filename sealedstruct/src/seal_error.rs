@@ -0,0 +1,125 @@
+//! Errors, which return the raw value they failed to seal
+
+use crate::{PathSegment, Result, Sealable, ValidationErrors, ValidationResultExtensions};
+
+/// Returned by [crate::TrySealable::try_seal]: The errors and the untouched raw value
+///
+/// This allows to show the input again or to fix a single field and retry, without cloning upfront.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{errors}")]
+pub struct SealError<T> {
+    pub errors: ValidationErrors,
+    pub raw: T,
+}
+
+/// Result of [crate::TrySealable::try_seal]
+pub type SealResult<T> = std::result::Result<<T as Sealable>::Target, SealError<T>>;
+
+impl<T> SealError<T> {
+    pub fn new(errors: ValidationErrors, raw: T) -> Self {
+        Self { errors, raw }
+    }
+
+    pub fn map_raw<TOther>(self, f: impl FnOnce(T) -> TOther) -> SealError<TOther> {
+        SealError {
+            errors: self.errors,
+            raw: f(self.raw),
+        }
+    }
+
+    fn map_errors(self, f: impl FnOnce(Result<()>) -> Result<()>) -> Self {
+        Self {
+            errors: f(Err(self.errors)).expect_err("Mapping errors keeps them"),
+            raw: self.raw,
+        }
+    }
+}
+
+impl<T> From<SealError<T>> for ValidationErrors {
+    fn from(error: SealError<T>) -> Self {
+        error.errors
+    }
+}
+
+/// Counterpart of [ValidationResultExtensions] for [SealResult]
+///
+/// Values, which were sealed successfully, are opened again if another value fails,
+/// so the error always contains the complete raw value.
+pub trait SealResultExtensions {
+    type Raw: Sealable;
+    fn combine<T: Sealable>(self, other: SealResult<T>) -> SealResult<(Self::Raw, T)>;
+    /// Adds the errors of checks, which ran on the raw value before sealing. They are listed first.
+    fn with_check(self, check: Result<()>) -> Self;
+    fn prepend(self, segment: PathSegment) -> Self;
+    fn prepend_field(self, name: &'static str) -> Self;
+    fn prepend_variant(self, name: &'static str) -> Self;
+    fn prepend_index(self, index: usize) -> Self;
+}
+
+impl<TRaw: Sealable> SealResultExtensions for SealResult<TRaw> {
+    type Raw = TRaw;
+
+    fn combine<T: Sealable>(self, other: SealResult<T>) -> SealResult<(TRaw, T)> {
+        match (self, other) {
+            (Ok(a), Ok(b)) => Ok((a, b)),
+            (Ok(a), Err(b)) => Err(b.map_raw(|b| (TRaw::open(a), b))),
+            (Err(a), Ok(b)) => Err(a.map_raw(|a| (a, T::open(b)))),
+            (Err(a), Err(b)) => Err(SealError::new(
+                a.errors.combine_with(b.errors),
+                (a.raw, b.raw),
+            )),
+        }
+    }
+
+    fn with_check(self, check: Result<()>) -> Self {
+        match (check, self) {
+            (Ok(()), result) => result,
+            (Err(errors), Ok(sealed)) => Err(SealError::new(errors, TRaw::open(sealed))),
+            (Err(errors), Err(e)) => Err(SealError::new(errors.combine_with(e.errors), e.raw)),
+        }
+    }
+
+    fn prepend(self, segment: PathSegment) -> Self {
+        self.map_err(|e| e.map_errors(|errors| errors.prepend(segment)))
+    }
+
+    fn prepend_field(self, name: &'static str) -> Self {
+        self.prepend(PathSegment::Field(name.into()))
+    }
+
+    fn prepend_variant(self, name: &'static str) -> Self {
+        self.prepend(PathSegment::Variant(name.into()))
+    }
+
+    fn prepend_index(self, index: usize) -> Self {
+        self.prepend(PathSegment::Index(index))
+    }
+}
+
+/// Seals all items. If any of them fails, the errors of all items are reported,
+/// and the raw collection is rebuilt from all items, opening the valid ones again.
+pub(crate) fn try_seal_all<TItem, TTarget, TRaw>(
+    items: impl Iterator<Item = SealResult<TItem>>,
+) -> std::result::Result<TTarget, SealError<TRaw>>
+where
+    TItem: Sealable,
+    TTarget: FromIterator<TItem::Target>,
+    TRaw: FromIterator<TItem>,
+{
+    let results = items.collect::<Vec<_>>();
+    if results.iter().all(std::result::Result::is_ok) {
+        return Ok(results.into_iter().flatten().collect());
+    }
+    let mut errors = ValidationErrors::default();
+    let raw = results
+        .into_iter()
+        .map(|result| match result {
+            Ok(sealed) => TItem::open(sealed),
+            Err(e) => {
+                errors = std::mem::take(&mut errors).combine_with(e.errors);
+                e.raw
+            }
+        })
+        .collect();
+    Err(SealError::new(errors, raw))
+}
@@ -0,0 +1,230 @@
+//! Serde representations of [ValidationErrors] for API responses
+//!
+//! ```json
+//! [{ "fields": ["numbers.int8"], "reason": "must be <100", "code": "invalid" }]
+//! ```
+//!
+//! Fields use the dotted notation of [FieldPath]. `params` is omitted if there are none.
+
+use crate::{FieldPath, ValidationError, ValidationErrors};
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+impl Serialize for FieldPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(FieldPath::from)
+    }
+}
+
+impl Serialize for ValidationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let has_params = self.params().next().is_some();
+        let mut state =
+            serializer.serialize_struct("ValidationError", 3 + usize::from(has_params))?;
+        state.serialize_field("fields", self.fields.as_slice())?;
+        state.serialize_field("reason", &self.reason)?;
+        state.serialize_field("code", self.code())?;
+        if has_params {
+            state.serialize_field("params", &Params(self))?;
+        } else {
+            state.skip_field("params")?;
+        }
+        state.end()
+    }
+}
+
+struct Params<'a>(&'a ValidationError);
+
+impl Serialize for Params<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.params())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "ValidationError")]
+struct ErrorRepr {
+    fields: Vec<FieldPath>,
+    reason: String,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    params: OrderedMap<String>,
+}
+
+impl<'de> Deserialize<'de> for ValidationError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ErrorRepr::deserialize(deserializer)?;
+        let mut fields = repr.fields.into_iter();
+        let first = fields.next().unwrap_or_default();
+        let mut error = ValidationError::on_fields(first, fields, repr.reason);
+        if let Some(code) = repr
+            .code
+            .filter(|code| code != ValidationError::DEFAULT_CODE)
+        {
+            error = error.with_code(code);
+        }
+        for (name, value) in repr.params.0 {
+            error = error.with_param(name, value);
+        }
+        Ok(error)
+    }
+}
+
+impl Serialize for ValidationErrors {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidationErrors {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<ValidationError>::deserialize(deserializer)
+            .map(|errors| ValidationErrors(errors.into_iter().collect()))
+    }
+}
+
+/// Groups [ValidationErrors] by field: `{ "numbers.int8": ["must be <100"] }`
+///
+/// Use it with `#[serde(with = "sealedstruct::by_field")]`. Fields are ordered by their first error.
+/// Codes and params are not part of this form, so deserialized errors use the default code.
+pub mod by_field {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        errors: &ValidationErrors,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(crate::display::group_by_field(errors))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ValidationErrors, D::Error> {
+        let groups = OrderedMap::<Vec<String>>::deserialize(deserializer)?;
+        Ok(ValidationErrors(
+            groups
+                .0
+                .into_iter()
+                .flat_map(|(field, reasons)| {
+                    reasons.into_iter().map(move |reason| {
+                        ValidationError::on_field(FieldPath::parse(&field), reason)
+                    })
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Map, which keeps the order of its entries
+struct OrderedMap<T>(Vec<(String, T)>);
+
+impl<T> Default for OrderedMap<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OrderedMap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedMapVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for OrderedMapVisitor<T> {
+            type Value = OrderedMap<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedMap(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedMapVisitor(std::marker::PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Result, ValidationResultExtensions};
+
+    fn errors() -> ValidationErrors {
+        let first: Result<()> = ValidationError::new("must be at most 100, got 127")
+            .with_code("range.max")
+            .with_param("max", 100)
+            .with_param("actual", 127)
+            .into();
+        let first = first
+            .prepend_path("int8")
+            .prepend_index(3)
+            .prepend_path("numbers");
+        let second: Result<()> =
+            ValidationError::on_fields("from", ["to"], "from must be smaller than to").into();
+        first.combine(second).unwrap_err()
+    }
+
+    #[test]
+    fn list_roundtrip() {
+        let json = serde_json::to_string(&errors()).unwrap();
+        assert_eq!(
+            serde_json::json!([
+                {
+                    "fields": ["numbers[3].int8"],
+                    "reason": "must be at most 100, got 127",
+                    "code": "range.max",
+                    "params": { "max": "100", "actual": "127" }
+                },
+                {
+                    "fields": ["from", "to"],
+                    "reason": "from must be smaller than to",
+                    "code": "invalid"
+                }
+            ]),
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        );
+        assert_eq!(
+            errors(),
+            serde_json::from_str::<ValidationErrors>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn by_field_roundtrip() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Response {
+            #[serde(with = "crate::by_field")]
+            errors: ValidationErrors,
+        }
+
+        let json = serde_json::to_string(&Response { errors: errors() }).unwrap();
+        assert_eq!(
+            r#"{"errors":{"numbers[3].int8":["must be at most 100, got 127"],"from":["from must be smaller than to"],"to":["from must be smaller than to"]}}"#,
+            json
+        );
+        let response: Response = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            vec!["numbers[3].int8", "from", "to"],
+            response
+                .errors
+                .iter()
+                .flat_map(ValidationError::iter_fields)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,22 +1,49 @@
-use crate::{Result, Sealable, ValidationResultExtensions};
+use crate::{
+    diff, seal_error::try_seal_all, ChangeKind, Checkable, FieldChange, PathSegment, Result,
+    SealResult, SealResultExtensions, Sealable, Sealed, TrySealable, ValidationResultExtensions,
+};
 use std::{
     borrow::Borrow,
     collections::{HashMap, HashSet},
+    fmt::Debug,
     hash::Hash,
 };
 
+/// Collects all sealed items. Unlike `collect()`, it reports the errors of all invalid items.
+// try_fold would stop at the first error
+#[allow(clippy::manual_try_fold)]
+fn seal_all<TItem, TTarget: Default + Extend<TItem>>(
+    items: impl Iterator<Item = Result<TItem>>,
+) -> Result<TTarget> {
+    items.fold(Ok(TTarget::default()), |acc, item| {
+        acc.combine(item).map(|(mut target, item)| {
+            target.extend(Some(item));
+            target
+        })
+    })
+}
+
+/// Reports the errors of all items
+// try_fold would stop at the first error
+#[allow(clippy::manual_try_fold)]
+fn check_all<T>(results: impl Iterator<Item = Result<T>>) -> Result<()> {
+    results.fold(Ok(()), |acc, result| acc.combine(result).map(|_| ()))
+}
+
 impl<TKey, TValue> Sealable for HashMap<TKey, TValue>
 where
-    TKey: Sealable + Hash + Eq,
+    TKey: Sealable + Hash + Eq + Debug,
     TValue: Sealable,
     TKey::Target: Hash + Eq + Borrow<TKey>,
 {
     type Target = HashMap<TKey::Target, TValue::Target>;
 
     fn seal(self) -> Result<Self::Target> {
-        self.into_iter()
-            .map(|(key, value)| key.seal().combine(value.seal()))
-            .collect()
+        seal_all(self.into_iter().map(|(key, value)| {
+            // The key is consumed by seal, so its path is formatted upfront
+            let path = PathSegment::key(&key);
+            key.seal().combine(value.seal()).prepend(path)
+        }))
     }
 
     fn open(sealed: Self::Target) -> Self {
@@ -41,6 +68,67 @@ where
         }
         true
     }
+
+    /// Keys are listed in the iteration order of the raw map, followed by the removed ones
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        for (key, value) in self.iter() {
+            let segment = PathSegment::key(key);
+            match other.get(key) {
+                Some(other_value) => {
+                    changes.extend(diff::prepend(value.diff(other_value), segment))
+                }
+                None => changes.push(FieldChange::new(ChangeKind::Added).prepend(segment)),
+            }
+        }
+        for key in other.keys().map(Borrow::<TKey>::borrow) {
+            if self.contains_key(key) {
+                continue;
+            }
+            let segment = PathSegment::key(key);
+            changes.push(FieldChange::new(ChangeKind::Removed).prepend(segment));
+        }
+        changes
+    }
+}
+
+impl<TKey, TValue> TrySealable for HashMap<TKey, TValue>
+where
+    TKey: TrySealable + Hash + Eq + Debug,
+    TValue: TrySealable,
+    TKey::Target: Hash + Eq + Borrow<TKey>,
+{
+    fn try_seal(self) -> SealResult<Self> {
+        try_seal_all(self.into_iter().map(|(key, value)| {
+            // The key is consumed by seal, so its path is formatted upfront
+            let path = PathSegment::key(&key);
+            key.try_seal().combine(value.try_seal()).prepend(path)
+        }))
+    }
+}
+
+impl<TKey, TValue> Checkable for HashMap<TKey, TValue>
+where
+    TKey: Checkable + Hash + Eq + Debug,
+    TValue: Checkable,
+    TKey::Target: Hash + Eq + Borrow<TKey>,
+{
+    fn check_sealable(&self) -> Result<()> {
+        check_all(self.iter().map(|(key, value)| {
+            key.check_sealable()
+                .combine(value.check_sealable())
+                .prepend_key(key)
+        }))
+    }
+}
+
+impl<TKey, TValue> Sealed for HashMap<TKey, TValue>
+where
+    TKey: Sealed + Hash + Eq + Borrow<TKey::Raw>,
+    TKey::Raw: Hash + Eq + Debug,
+    TValue: Sealed,
+{
+    type Raw = HashMap<TKey::Raw, TValue::Raw>;
 }
 
 impl<T> Sealable for Vec<T>
@@ -50,7 +138,11 @@ where
     type Target = Vec<T::Target>;
 
     fn seal(self) -> Result<Self::Target> {
-        self.into_iter().map(Sealable::seal).collect()
+        seal_all(
+            self.into_iter()
+                .enumerate()
+                .map(|(index, item)| item.seal().prepend_index(index)),
+        )
     }
 
     fn open(sealed: Self::Target) -> Self {
@@ -68,17 +160,62 @@ where
                 .zip(other_iter.by_ref())
                 .fold(true, |acc, (a, b)| acc && a.partial_eq(b))
     }
+
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        for (index, (a, b)) in self.iter().zip(other.iter()).enumerate() {
+            changes.extend(diff::prepend_index(a.diff(b), index));
+        }
+        let (longer, kind) = if self.len() > other.len() {
+            (self.len(), ChangeKind::Added)
+        } else {
+            (other.len(), ChangeKind::Removed)
+        };
+        for index in self.len().min(other.len())..longer {
+            changes.push(FieldChange::new(kind).prepend(PathSegment::Index(index)));
+        }
+        changes
+    }
+}
+
+impl<T: TrySealable> TrySealable for Vec<T> {
+    fn try_seal(self) -> SealResult<Self> {
+        try_seal_all(
+            self.into_iter()
+                .enumerate()
+                .map(|(index, item)| item.try_seal().prepend_index(index)),
+        )
+    }
+}
+
+impl<T: Checkable> Checkable for Vec<T> {
+    fn check_sealable(&self) -> Result<()> {
+        check_all(
+            self.iter()
+                .enumerate()
+                .map(|(index, item)| item.check_sealable().prepend_index(index)),
+        )
+    }
+}
+
+impl<T: Sealed> Sealed for Vec<T> {
+    type Raw = Vec<T::Raw>;
 }
 
 impl<T> Sealable for HashSet<T>
 where
-    T: Sealable + Hash + Eq,
+    T: Sealable + Hash + Eq + Debug,
     T::Target: Hash + Eq + Borrow<T>,
 {
     type Target = HashSet<T::Target>;
 
+    // Elements of a set have no position, so they are identified like the keys of a map
     fn seal(self) -> Result<Self::Target> {
-        self.into_iter().map(Sealable::seal).collect()
+        seal_all(self.into_iter().map(|item| {
+            // The element is consumed by seal, so its path is formatted upfront
+            let path = PathSegment::key(&item);
+            item.seal().prepend(path)
+        }))
     }
     fn open(sealed: Self::Target) -> Self {
         sealed.into_iter().map(|value| T::open(value)).collect()
@@ -97,6 +234,40 @@ where
     }
 }
 
+impl<T> TrySealable for HashSet<T>
+where
+    T: TrySealable + Hash + Eq + Debug,
+    T::Target: Hash + Eq + Borrow<T>,
+{
+    fn try_seal(self) -> SealResult<Self> {
+        try_seal_all(self.into_iter().map(|item| {
+            let path = PathSegment::key(&item);
+            item.try_seal().prepend(path)
+        }))
+    }
+}
+
+impl<T> Checkable for HashSet<T>
+where
+    T: Checkable + Hash + Eq + Debug,
+    T::Target: Hash + Eq + Borrow<T>,
+{
+    fn check_sealable(&self) -> Result<()> {
+        check_all(
+            self.iter()
+                .map(|item| item.check_sealable().prepend_key(item)),
+        )
+    }
+}
+
+impl<T> Sealed for HashSet<T>
+where
+    T: Sealed + Hash + Eq + Borrow<T::Raw>,
+    T::Raw: Hash + Eq + Debug,
+{
+    type Raw = HashSet<T::Raw>;
+}
+
 impl<T> Sealable for Option<T>
 where
     T: Sealable,
@@ -120,4 +291,35 @@ where
             (Some(a), Some(b)) => a.partial_eq(b),
         }
     }
+
+    fn diff(&self, other: &Self::Target) -> Vec<FieldChange> {
+        match (self, other) {
+            (None, None) => Vec::new(),
+            (Some(_), None) => vec![FieldChange::new(ChangeKind::Added)],
+            (None, Some(_)) => vec![FieldChange::new(ChangeKind::Removed)],
+            (Some(a), Some(b)) => a.diff(b),
+        }
+    }
+}
+
+impl<T: TrySealable> TrySealable for Option<T> {
+    fn try_seal(self) -> SealResult<Self> {
+        match self {
+            Some(x) => x.try_seal().map(Some).map_err(|e| e.map_raw(Some)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: Checkable> Checkable for Option<T> {
+    fn check_sealable(&self) -> Result<()> {
+        match self {
+            Some(x) => x.check_sealable(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Sealed> Sealed for Option<T> {
+    type Raw = Option<T::Raw>;
 }
@@ -0,0 +1,180 @@
+//! Hierarchical view of [ValidationErrors], e.g. to attach messages to sub-forms
+
+use crate::{FieldPath, PathSegment, ValidationError, ValidationErrors};
+
+/// Errors grouped by the segments of their fields
+///
+/// Each node holds the errors on its own path and a child per following segment.
+/// Errors on multiple fields are part of each of these nodes.
+/// Children are ordered by their first error.
+#[derive(Debug, Default)]
+pub struct ErrorTree<'a> {
+    errors: Vec<&'a ValidationError>,
+    children: Vec<(PathSegment, ErrorTree<'a>)>,
+}
+
+impl<'a> ErrorTree<'a> {
+    fn insert(&mut self, segments: &[PathSegment], error: &'a ValidationError) {
+        match segments.split_first() {
+            None => self.errors.push(error),
+            Some((segment, rest)) => {
+                let position = match self.children.iter().position(|(x, _)| x.matches(segment)) {
+                    Some(position) => position,
+                    None => {
+                        self.children.push((segment.clone(), ErrorTree::default()));
+                        self.children.len() - 1
+                    }
+                };
+                self.children[position].1.insert(rest, error);
+            }
+        }
+    }
+
+    /// Errors on the path of this node. Their fields are not stripped.
+    pub fn errors(&self) -> &[&'a ValidationError] {
+        &self.errors
+    }
+
+    pub fn reasons(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.errors.iter().map(|error| error.reason.as_str())
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = (&PathSegment, &ErrorTree<'a>)> {
+        self.children
+            .iter()
+            .map(|(segment, child)| (segment, child))
+    }
+
+    pub fn child(&self, segment: &PathSegment) -> Option<&ErrorTree<'a>> {
+        self.children
+            .iter()
+            .find(|(x, _)| x.matches(segment))
+            .map(|(_, child)| child)
+    }
+
+    /// Strings are parsed in the dotted notation, e.g. `"numbers[3]"`
+    pub fn get(&self, path: impl Into<FieldPath>) -> Option<&ErrorTree<'a>> {
+        path.into()
+            .segments()
+            .iter()
+            .try_fold(self, |node, segment| node.child(segment))
+    }
+
+    /// Whether neither this node nor any of its children has errors
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.children.is_empty()
+    }
+}
+
+impl ValidationErrors {
+    pub fn to_tree(&self) -> ErrorTree<'_> {
+        let mut root = ErrorTree::default();
+        for error in self.iter() {
+            for field in error.iter_fields() {
+                root.insert(field.segments(), error);
+            }
+        }
+        root
+    }
+
+    /// Errors within `prefix`, with `prefix` stripped from their fields
+    ///
+    /// Fields outside of `prefix` are removed. Returns `None`, if no error remains.
+    pub fn at(&self, prefix: impl Into<FieldPath>) -> Option<ValidationErrors> {
+        let prefix = prefix.into();
+        let errors = self
+            .iter()
+            .filter_map(|error| {
+                let mut fields = error
+                    .iter_fields()
+                    .filter_map(|field| field.strip_prefix(&prefix))
+                    .peekable();
+                fields.peek()?;
+                let mut result = error.clone();
+                result.fields = fields.collect();
+                Some(result)
+            })
+            .collect::<smallvec::SmallVec<_>>();
+        (!errors.is_empty()).then_some(ValidationErrors(errors))
+    }
+
+    /// Errors on exactly this field, e.g. `"numbers[3].int8"`
+    pub fn for_field(&self, field: impl Into<FieldPath>) -> impl Iterator<Item = &ValidationError> {
+        let field = field.into();
+        self.iter().filter(move |error| {
+            error
+                .iter_fields()
+                .any(|x| x.strip_prefix(&field).is_some_and(|rest| rest.is_root()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Result, ValidationResultExtensions};
+
+    fn errors() -> ValidationErrors {
+        let int8: Result<()> = ValidationError::new("must be <100").into();
+        let int16: Result<()> = ValidationError::new("max is not allowed").into();
+        let numbers = int8
+            .prepend_field("int8")
+            .combine(int16.prepend_field("int16"))
+            .prepend_index(3)
+            .prepend_field("numbers");
+        let range: Result<()> =
+            ValidationError::on_fields("from", ["numbers[3].int8"], "from must be smaller").into();
+        let root: Result<()> = ValidationError::new("is locked").into();
+        numbers.combine(range).combine(root).unwrap_err()
+    }
+
+    #[test]
+    fn tree() {
+        let errors = errors();
+        let tree = errors.to_tree();
+        assert_eq!(vec!["is locked"], tree.reasons().collect::<Vec<_>>());
+        assert_eq!(
+            vec!["numbers", "from"],
+            tree.children()
+                .map(|(segment, _)| FieldPath::from(segment.clone()).to_string())
+                .collect::<Vec<_>>()
+        );
+        let int8 = tree.get("numbers[3].int8").unwrap();
+        assert_eq!(
+            vec!["must be <100", "from must be smaller"],
+            int8.reasons().collect::<Vec<_>>()
+        );
+        assert!(tree.get("numbers[4]").is_none());
+        assert!(!tree.get("numbers").unwrap().is_empty());
+    }
+
+    #[test]
+    fn at_strips_prefix() {
+        let numbers = errors().at("numbers[3]").unwrap();
+        assert_eq!(
+            vec![vec!["int8"], vec!["int16"], vec!["int8"]],
+            numbers
+                .iter()
+                .map(|error| error
+                    .iter_fields()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(4, errors().at("").unwrap().iter().count());
+        assert!(errors().at("name").is_none());
+    }
+
+    #[test]
+    fn for_field() {
+        let errors = errors();
+        assert_eq!(
+            vec!["must be <100", "from must be smaller"],
+            errors
+                .for_field("numbers[3].int8")
+                .map(|error| error.reason.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, errors.for_field("numbers").count());
+    }
+}
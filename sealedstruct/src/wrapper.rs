@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use crate::Sealable;
+use crate::{Checkable, Sealable, Sealed, TrySealable};
 
 /// Used to wrap Values you have no control over
 /// It delegates most standard traits to it's inner component
@@ -24,6 +24,22 @@ impl<T: PartialEq> Sealable for IntoNestedWrapper<T> {
     }
 }
 
+impl<T: PartialEq> TrySealable for IntoNestedWrapper<T> {
+    fn try_seal(self) -> crate::SealResult<Self> {
+        Ok(self)
+    }
+}
+
+impl<T: PartialEq> Checkable for IntoNestedWrapper<T> {
+    fn check_sealable(&self) -> crate::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: PartialEq> Sealed for IntoNestedWrapper<T> {
+    type Raw = Self;
+}
+
 impl<T: PartialEq> Deref for IntoNestedWrapper<T> {
     type Target = T;
 
@@ -0,0 +1,126 @@
+//! Types shared by the test crates, which require the `uuid` and `serde` features
+
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Visibility for Raw must be restricted to pub, pub (crate), or pub(super)
+// The hole procedure just makes sense if this is contained in a submodule.
+// All fields of the sealed struct would otherwise be accessible anyway
+#[derive(
+    PartialEq, Default, Debug, Clone, sealedstruct::Nested, serde::Serialize, serde::Deserialize,
+)]
+#[sealedDerive(Clone)]
+#[sealed(env, merge, check_by_clone)]
+#[serde(deny_unknown_fields)]
+pub struct NumbersRaw {
+    pub int8: i8,
+    pub int16: i16,
+    pub int32: i32,
+    pub int64: i64,
+    pub int128: i128,
+}
+
+#[derive(
+    PartialEq,
+    Default,
+    Debug,
+    sealedstruct::Nested,
+    sealedstruct::TryIntoNested,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct OtherRaw {
+    pub test: i32,
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub(crate) struct WrapperRaw {
+    pub numbers: NumbersRaw,
+    pub ip: std::net::IpAddr,
+    pub optional: Option<i32>,
+    pub direction: DirectionRaw,
+    pub always: AlwaysValid,
+    pub map: Vec<NumbersRaw>,
+    pub id: Uuid,
+    pub hash_map: HashMap<Uuid, NumbersRaw>,
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub enum DirectionRaw {
+    Up,
+    Down,
+    Left(i8),
+    Right { millis: i8 },
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub enum ShapeRaw {
+    Circle { radius: NumbersRaw },
+    Polygon(Vec<NumbersRaw>, Option<i32>),
+    Empty,
+}
+
+#[derive(PartialEq, Debug, sealedstruct::IntoNested)]
+pub enum AlwaysValid {
+    Bar,
+}
+
+#[allow(dead_code)]
+#[derive(PartialEq, Debug, sealedstruct::IntoNested)]
+pub struct AlwaysValidStruct {
+    foo: i32,
+    bar: i32,
+}
+
+impl Default for WrapperRaw {
+    fn default() -> Self {
+        Self {
+            numbers: Default::default(),
+            ip: std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
+            optional: None,
+            direction: DirectionRaw::Down,
+            always: AlwaysValid::Bar,
+            map: Default::default(),
+            id: Uuid::from_u128(1),
+            hash_map: Default::default(),
+        }
+    }
+}
+
+impl NumbersRaw {
+    // All fields are Copy, so the checks work by reference
+    fn results(&self) -> NumbersResult {
+        NumbersResult {
+            int8: if self.int8 < 100 {
+                Ok(self.int8)
+            } else {
+                sealedstruct::ValidationError::new("must be <100").into()
+            },
+            int16: if self.int16 != i16::MAX {
+                Ok(self.int16)
+            } else {
+                sealedstruct::ValidationError::new("max is not allowed").into()
+            },
+            int32: Ok(self.int32),
+            int64: Ok(self.int64),
+            int128: Ok(self.int128),
+        }
+    }
+}
+
+impl sealedstruct::TryIntoNested for NumbersRaw {
+    type Target = NumbersInner;
+
+    fn try_into_nested(self) -> sealedstruct::Result<Self::Target> {
+        self.results().into()
+    }
+}
+
+/// Invalid, as `int8` must be smaller than 100
+pub fn invalid_numbers() -> NumbersRaw {
+    NumbersRaw {
+        int8: 127,
+        ..Default::default()
+    }
+}
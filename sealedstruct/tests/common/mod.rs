@@ -0,0 +1,17 @@
+//! Fixtures and helpers shared by the integration tests
+// Each test crate uses only some of them
+#![allow(dead_code, unused_imports)]
+
+#[cfg(all(feature = "uuid", feature = "serde"))]
+mod fixtures;
+#[cfg(all(feature = "uuid", feature = "serde"))]
+pub use fixtures::*;
+
+/// Paths of the fields, on which the errors are reported
+pub fn fields(errors: &sealedstruct::ValidationErrors) -> Vec<String> {
+    errors
+        .iter()
+        .flat_map(sealedstruct::ValidationError::iter_fields)
+        .map(ToString::to_string)
+        .collect()
+}
@@ -0,0 +1,57 @@
+use common::*;
+use sealedstruct::prelude::*;
+
+mod common;
+
+#[test]
+fn diff() {
+    use sealedstruct::{ChangeKind, FieldChange};
+
+    let base = || WrapperRaw {
+        map: vec![NumbersRaw::default(), NumbersRaw::default()],
+        direction: DirectionRaw::Right { millis: 1 },
+        ..Default::default()
+    };
+    let sealed = base().seal().unwrap();
+    let changes = |raw: &WrapperRaw| {
+        raw.diff(&sealed)
+            .into_iter()
+            .map(|FieldChange { field, kind }| (field.to_string(), kind))
+            .collect::<Vec<_>>()
+    };
+
+    let mut raw = base();
+    assert!(changes(&raw).is_empty());
+
+    raw.numbers.int8 = 1;
+    raw.optional = Some(1);
+    raw.direction = DirectionRaw::Right { millis: 2 };
+    raw.map[1].int16 = 1;
+    raw.map.pop();
+    raw.hash_map
+        .insert(uuid::Uuid::from_u128(2), NumbersRaw::default());
+    assert_eq!(
+        vec![
+            ("numbers.int8".to_string(), ChangeKind::Changed),
+            ("optional".to_string(), ChangeKind::Added),
+            ("direction.Right.millis".to_string(), ChangeKind::Changed),
+            ("map[1]".to_string(), ChangeKind::Removed),
+            (
+                r#"hash_map["00000000-0000-0000-0000-000000000002"]"#.to_string(),
+                ChangeKind::Added
+            ),
+        ],
+        changes(&raw)
+    );
+
+    raw = base();
+    raw.direction = DirectionRaw::Left(1);
+    raw.map.push(NumbersRaw::default());
+    assert_eq!(
+        vec![
+            ("direction".to_string(), ChangeKind::Changed),
+            ("map[2]".to_string(), ChangeKind::Added),
+        ],
+        changes(&raw)
+    );
+}
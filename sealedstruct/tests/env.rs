@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use common::*;
+
+mod common;
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(env)]
+pub struct EnvRaw {
+    #[sealed(non_empty)]
+    pub name: String,
+    pub port: Option<u16>,
+    pub numbers: NumbersRaw,
+    pub fallback: Option<NumbersRaw>,
+}
+
+#[test]
+fn override_from_env() {
+    let raw = || EnvRaw {
+        name: "service".into(),
+        port: None,
+        numbers: NumbersRaw::default(),
+        fallback: None,
+    };
+    let vars = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    let sealed = sealedstruct::env::seal(
+        raw(),
+        "APP",
+        &vars(&[
+            ("APP__PORT", "8080"),
+            ("APP__FALLBACK__INT32", "3"),
+            ("OTHER__NAME", ""),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(Some(8080), sealed.port);
+    assert_eq!(3, sealed.fallback.as_ref().unwrap().int32);
+    assert_eq!("service", sealed.name);
+
+    let errors = sealedstruct::env::seal(
+        raw(),
+        "APP",
+        &vars(&[
+            ("APP__NAME", ""),
+            ("APP__NUMBERS__INT8", "127"),
+            ("APP__NUMBERS__INT16", "many"),
+        ]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        vec![
+            (
+                "numbers.int16".to_string(),
+                "env.parse",
+                "APP__NUMBERS__INT16"
+            ),
+            ("name".to_string(), "non_empty", "APP__NAME"),
+            ("numbers.int8".to_string(), "invalid", "APP__NUMBERS__INT8"),
+        ],
+        errors
+            .iter()
+            .map(|error| (
+                error.iter_fields().next().unwrap().to_string(),
+                error.code(),
+                error.param("variable").unwrap()
+            ))
+            .collect::<Vec<_>>()
+    );
+}
@@ -1,7 +1,10 @@
 use sealedstruct::IntoNested;
 
+// Only checks that the derive compiles for generic types
+#[allow(dead_code)]
 #[derive(IntoNested, PartialEq)]
 struct MyGeneric<T: std::fmt::Debug>(T, T);
 
+#[allow(dead_code)]
 #[derive(IntoNested, PartialEq)]
 struct MyGeneric2<T>(T, T);
@@ -0,0 +1,114 @@
+use common::fields;
+use sealedstruct::json_patch::{self, Operation};
+use serde_json::json;
+
+mod common;
+
+#[derive(
+    PartialEq,
+    Debug,
+    sealedstruct::Nested,
+    sealedstruct::TryIntoNested,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct ConfigRaw {
+    #[sealed(length(min = 1))]
+    pub name: String,
+    #[sealed(range(max = 10))]
+    pub retries: u8,
+    pub hosts: Vec<String>,
+    pub comment: Option<String>,
+}
+
+fn config() -> Config {
+    ConfigRaw {
+        name: "service".into(),
+        retries: 3,
+        hosts: vec!["a".into(), "b".into()],
+        comment: None,
+    }
+    .seal()
+    .unwrap()
+}
+
+fn operations(patch: serde_json::Value) -> Vec<Operation> {
+    serde_json::from_value(patch).unwrap()
+}
+
+#[test]
+fn applies_all_operations() {
+    let patch = operations(json!([
+        { "op": "test", "path": "/retries", "value": 3 },
+        { "op": "replace", "path": "/retries", "value": 5 },
+        { "op": "add", "path": "/hosts/-", "value": "c" },
+        { "op": "remove", "path": "/hosts/0" },
+        { "op": "copy", "from": "/name", "path": "/comment" },
+        { "op": "move", "from": "/hosts/1", "path": "/hosts/0" }
+    ]));
+    let patched = json_patch::apply(config(), &patch).unwrap();
+    assert_eq!(
+        ConfigRaw {
+            name: "service".into(),
+            retries: 5,
+            hosts: vec!["c".into(), "b".into()],
+            comment: Some("service".into()),
+        },
+        patched
+    );
+}
+
+#[test]
+fn reports_patch_failures_on_their_path() {
+    let errors = json_patch::apply(
+        config(),
+        &operations(json!([{ "op": "test", "path": "/retries", "value": 4 }])),
+    )
+    .unwrap_err();
+    assert_eq!(vec!["retries"], fields(&errors));
+    assert_eq!("json_patch.test", errors.iter().next().unwrap().code());
+
+    let errors = json_patch::apply(
+        config(),
+        &operations(json!([{ "op": "remove", "path": "/hosts/7" }])),
+    )
+    .unwrap_err();
+    assert_eq!(vec!["hosts[7]"], fields(&errors));
+    assert_eq!(
+        "json_patch.invalid_index",
+        errors.iter().next().unwrap().code()
+    );
+
+    let errors = json_patch::apply(
+        config(),
+        &operations(json!([{ "op": "replace", "path": "/retries", "value": "many" }])),
+    )
+    .unwrap_err();
+    assert_eq!(vec!["retries"], fields(&errors));
+    assert_eq!(
+        "json_patch.deserialize",
+        errors.iter().next().unwrap().code()
+    );
+}
+
+#[test]
+fn reports_validation_failures_like_seal() {
+    let errors = json_patch::apply(
+        config(),
+        &operations(json!([
+            { "op": "replace", "path": "/name", "value": "" },
+            { "op": "replace", "path": "/retries", "value": 11 }
+        ])),
+    )
+    .unwrap_err();
+    let sealed = ConfigRaw {
+        name: "".into(),
+        retries: 11,
+        hosts: vec!["a".into(), "b".into()],
+        comment: None,
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(sealed, errors);
+    assert_eq!(vec!["name", "retries"], fields(&errors));
+}
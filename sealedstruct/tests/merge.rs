@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use common::*;
+use sealedstruct::prelude::*;
+
+mod common;
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(merge)]
+pub struct ServiceRaw {
+    pub retries: Option<u8>,
+    #[sealed(merge = "append")]
+    pub hosts: Vec<String>,
+    pub numbers: Option<NumbersRaw>,
+    pub limits: HashMap<String, NumbersRaw>,
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(merge)]
+pub struct TimeoutsRaw {
+    #[sealed(custom = at_most_ten)]
+    pub connect: Option<u32>,
+    pub read: Option<u32>,
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(merge)]
+pub struct ClientRaw {
+    pub name: Option<String>,
+    pub timeouts: Option<TimeoutsRaw>,
+}
+
+fn at_most_ten(timeout: &Option<u32>) -> sealedstruct::Result<()> {
+    match timeout {
+        Some(timeout) => sealedstruct::checks::range(timeout, None, Some(10)),
+        None => Ok(()),
+    }
+}
+
+#[test]
+fn merge_layers() {
+    let defaults = || ServiceRaw {
+        retries: Some(3),
+        hosts: vec!["a".into()],
+        numbers: Some(NumbersRaw::default()),
+        limits: [("a".to_string(), invalid_numbers())].into_iter().collect(),
+    };
+    let env = || ServiceRaw {
+        retries: None,
+        hosts: vec!["b".into()],
+        numbers: Some(invalid_numbers()),
+        limits: [("b".to_string(), NumbersRaw::default())]
+            .into_iter()
+            .collect(),
+    };
+
+    let mut merged = defaults();
+    merged.merge(env());
+    assert_eq!(Some(3), merged.retries);
+    assert_eq!(vec!["a", "b"], merged.hosts);
+    assert_eq!(Some(invalid_numbers()), merged.numbers);
+    assert_eq!(2, merged.limits.len());
+
+    let errors = sealedstruct::merge::Layers::new("defaults", defaults())
+        .layer("env", env())
+        .seal()
+        .unwrap_err();
+    assert_eq!(
+        vec![
+            ("numbers.int8".to_string(), Some("env")),
+            (r#"limits["a"].int8"#.to_string(), Some("defaults")),
+        ],
+        errors
+            .iter()
+            .map(|error| (
+                error.iter_fields().next().unwrap().to_string(),
+                error.param("layer")
+            ))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn merge_optional_structs() {
+    let file = |connect| ClientRaw {
+        name: Some("client".into()),
+        timeouts: Some(TimeoutsRaw {
+            connect: Some(connect),
+            read: None,
+        }),
+    };
+    let env = || ClientRaw {
+        name: None,
+        timeouts: Some(TimeoutsRaw {
+            connect: None,
+            read: Some(2),
+        }),
+    };
+
+    let client = sealedstruct::merge::Layers::new("file", file(1))
+        .layer("env", env())
+        .seal()
+        .unwrap();
+    assert_eq!(Some("client"), client.name.as_deref());
+    let timeouts = client.timeouts.as_ref().unwrap();
+    assert_eq!((Some(1), Some(2)), (timeouts.connect, timeouts.read));
+
+    let mut merged = file(1);
+    merged.merge(env());
+    let mut deep = file(1);
+    deep.merge_with(env(), sealedstruct::merge::Strategy::Deep);
+    assert_eq!(deep, merged);
+
+    // Only the fields, which a layer supplied, are tagged with it
+    let errors = sealedstruct::merge::Layers::new("file", file(20))
+        .layer("env", env())
+        .seal()
+        .unwrap_err();
+    assert_eq!(
+        vec![("timeouts.connect".to_string(), Some("file"))],
+        errors
+            .iter()
+            .map(|error| (
+                error.iter_fields().next().unwrap().to_string(),
+                error.param("layer")
+            ))
+            .collect::<Vec<_>>()
+    );
+}
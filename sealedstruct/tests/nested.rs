@@ -1,110 +1,9 @@
 use std::collections::HashMap;
 
-use config::*;
+use common::*;
 use sealedstruct::prelude::*;
 
-mod config {
-    use std::collections::HashMap;
-    use uuid::Uuid;
-
-    // Visibility for Raw must be restricted to pub, pub (crate), or pub(super)
-    // The hole procedure just makes sense if this is contained in a submodule.
-    // All fields of the sealed struct would otherwise be accessible anyway
-    #[derive(
-        PartialEq, Default, Debug, sealedstruct::Nested, serde::Serialize, serde::Deserialize,
-    )]
-    #[sealedDerive(Clone)]
-    #[serde(deny_unknown_fields)]
-    pub struct NumbersRaw {
-        pub int8: i8,
-        pub int16: i16,
-        pub int32: i32,
-        pub int64: i64,
-        pub int128: i128,
-    }
-
-    #[derive(
-        PartialEq,
-        Default,
-        Debug,
-        sealedstruct::Nested,
-        sealedstruct::TryIntoNested,
-        serde::Serialize,
-        serde::Deserialize,
-    )]
-    #[serde(deny_unknown_fields)]
-    pub struct OtherRaw {
-        pub test: i32,
-    }
-
-    #[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
-    pub(super) struct WrapperRaw {
-        pub numbers: NumbersRaw,
-        pub ip: std::net::IpAddr,
-        pub optional: Option<i32>,
-        pub direction: DirectionRaw,
-        pub always: AlwaysValid,
-        pub map: Vec<NumbersRaw>,
-        pub id: Uuid,
-        pub hash_map: HashMap<Uuid, NumbersRaw>,
-    }
-
-    #[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
-    pub enum DirectionRaw {
-        Up,
-        Down, //Left(i8),
-              //Right { millis: i8 }
-    }
-
-    #[derive(PartialEq, Debug, sealedstruct::IntoNested)]
-    pub enum AlwaysValid {
-        Bar,
-    }
-
-    #[derive(PartialEq, Debug, sealedstruct::IntoNested)]
-    pub struct AlwaysValidStruct {
-        foo: i32,
-        bar: i32,
-    }
-
-    impl Default for WrapperRaw {
-        fn default() -> Self {
-            Self {
-                numbers: Default::default(),
-                ip: std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
-                optional: None,
-                direction: DirectionRaw::Down,
-                always: AlwaysValid::Bar,
-                map: Default::default(),
-                id: Uuid::from_u128(1),
-                hash_map: Default::default(),
-            }
-        }
-    }
-
-    impl sealedstruct::TryIntoNested for NumbersRaw {
-        type Target = NumbersInner;
-
-        fn try_into_nested(self) -> sealedstruct::Result<Self::Target> {
-            NumbersResult {
-                int8: if self.int8 < 100 {
-                    Ok(self.int8)
-                } else {
-                    sealedstruct::ValidationError::new("must be <100").into()
-                },
-                int16: if self.int16 != i16::MAX {
-                    Ok(self.int16)
-                } else {
-                    sealedstruct::ValidationError::new("max is not allowed").into()
-                },
-                int32: Ok(self.int32),
-                int64: Ok(self.int64),
-                int128: Ok(self.int128),
-            }
-            .into()
-        }
-    }
-}
+mod common;
 
 #[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
 pub struct TupleStructRaw();
@@ -154,6 +53,20 @@ fn sealed_numbers() {
     )
 }
 
+#[test]
+fn deserialize_seals() {
+    let value: Numbers =
+        serde_json::from_str(r#"{"int8": 1, "int16": 2, "int32": 3, "int64": 4, "int128": 5}"#)
+            .unwrap();
+    assert_eq!(1, value.int8);
+
+    let error = serde_json::from_str::<Numbers>(
+        r#"{"int8": 127, "int16": 2, "int32": 3, "int64": 4, "int128": 5}"#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("must be <100"), "{error}");
+}
+
 #[test]
 fn error_path() {
     let r = WrapperRaw {
@@ -191,6 +104,100 @@ fn error_path() {
     }
 }
 
+#[test]
+fn collection_error_path() {
+    let errors = WrapperRaw {
+        map: vec![NumbersRaw::default(), invalid_numbers(), invalid_numbers()],
+        hash_map: [(uuid::Uuid::from_u128(2), invalid_numbers())]
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    }
+    .seal()
+    .unwrap_err();
+
+    assert_eq!(
+        vec![
+            "map[1].int8",
+            "map[2].int8",
+            r#"hash_map["00000000-0000-0000-0000-000000000002"].int8"#
+        ],
+        fields(&errors)
+    );
+
+    let keys = [("a", invalid_numbers()), ("b", NumbersRaw::default())]
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let errors = keys.seal().unwrap_err();
+    assert_eq!(vec!["[\"a\"].int8"], fields(&errors));
+}
+
+#[test]
+fn try_seal_returns_raw() {
+    let raw = || WrapperRaw {
+        numbers: NumbersRaw {
+            int8: 127,
+            ..Default::default()
+        },
+        map: vec![
+            NumbersRaw::default(),
+            NumbersRaw {
+                int16: i16::MAX,
+                ..Default::default()
+            },
+        ],
+        direction: DirectionRaw::Left(3),
+        ..Default::default()
+    };
+    let error = raw().try_seal().unwrap_err();
+    assert_eq!(raw(), error.raw);
+    assert_eq!(vec!["numbers.int8", "map[1].int16"], fields(&error.errors));
+
+    let error = UserRaw {
+        name: "admin".into(),
+        age: 36,
+        numbers: vec![NumbersRaw::default()],
+    }
+    .try_seal()
+    .unwrap_err();
+    assert_eq!("admin", error.raw.name);
+    assert_eq!(vec![NumbersRaw::default()], error.raw.numbers);
+}
+
+#[test]
+fn check_by_reference() {
+    let raw = || WrapperRaw {
+        numbers: invalid_numbers(),
+        map: vec![NumbersRaw::default(), invalid_numbers()],
+        hash_map: [(uuid::Uuid::from_u128(2), invalid_numbers())]
+            .into_iter()
+            .collect(),
+        direction: DirectionRaw::Left(3),
+        ..Default::default()
+    };
+    assert_eq!(
+        raw().seal().unwrap_err(),
+        raw().check_sealable().unwrap_err()
+    );
+    assert!(WrapperRaw::default().check_sealable().is_ok());
+
+    let shape = || ShapeRaw::Polygon(vec![invalid_numbers()], Some(1));
+    assert_eq!(
+        shape().seal().unwrap_err(),
+        shape().check_sealable().unwrap_err()
+    );
+
+    let user = || UserRaw {
+        name: "admin".into(),
+        age: 101,
+        numbers: vec![invalid_numbers()],
+    };
+    assert_eq!(
+        user().seal().unwrap_err(),
+        user().check_sealable().unwrap_err()
+    );
+}
+
 #[test]
 fn test_collection_types() {
     #[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
@@ -199,7 +206,7 @@ fn test_collection_types() {
     pub struct OuterRaw {
         map: Option<InnerRaw>,
     }
-    OuterResult {
+    let _ = OuterResult {
         map: Ok(None), //map: Ok([(1i32, InnerSealed(InnerInner {}))].into_iter().collect()),
     };
 
@@ -208,3 +215,302 @@ fn test_collection_types() {
         .collect::<HashMap<_, _>>();
     map.seal().unwrap();
 }
+
+#[test]
+fn enum_variants_with_fields() {
+    let circle = ShapeRaw::Circle {
+        radius: NumbersRaw::default(),
+    }
+    .seal()
+    .unwrap();
+    assert_eq!(
+        ShapeRaw::Circle {
+            radius: NumbersRaw::default()
+        },
+        circle
+    );
+    assert_ne!(ShapeRaw::Empty, circle);
+    assert_eq!(
+        ShapeRaw::Circle {
+            radius: NumbersRaw::default()
+        },
+        ShapeRaw::open(circle)
+    );
+
+    let polygon = ShapeRaw::Polygon(vec![NumbersRaw::default()], Some(1))
+        .seal()
+        .unwrap();
+    assert_eq!(
+        ShapeRaw::Polygon(vec![NumbersRaw::default()], Some(1)),
+        polygon
+    );
+    assert_ne!(ShapeRaw::Polygon(vec![], Some(1)), polygon);
+
+    let direction = WrapperRaw {
+        direction: DirectionRaw::Right { millis: 5 },
+        ..Default::default()
+    }
+    .seal()
+    .unwrap();
+    assert_eq!(DirectionInner::Right { millis: 5 }, *direction.direction);
+}
+
+#[test]
+fn enum_variant_error_path() {
+    let err = ShapeRaw::Circle {
+        radius: NumbersRaw {
+            int8: 127,
+            ..Default::default()
+        },
+    }
+    .seal()
+    .unwrap_err();
+    let error = err.into_iter().next().expect("One error");
+    assert_eq!(
+        vec!["Circle.radius.int8"],
+        error.iter_fields().collect::<Vec<_>>()
+    );
+}
+
+fn no_admin(name: &str) -> sealedstruct::Result<()> {
+    if name == "admin" {
+        sealedstruct::ValidationError::new("admin is reserved").into()
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub struct UserRaw {
+    #[sealed(length(min = 2, max = 8), custom = no_admin)]
+    pub name: String,
+    #[sealed(range(min = 0, max = 100))]
+    pub age: i8,
+    #[sealed(non_empty)]
+    pub numbers: Vec<NumbersRaw>,
+}
+
+#[test]
+fn field_attributes() {
+    let user = UserRaw {
+        name: "Ada".into(),
+        age: 36,
+        numbers: vec![NumbersRaw::default()],
+    };
+    assert!(user.seal().is_ok());
+
+    let errors = UserRaw {
+        name: "admin_too_long".into(),
+        age: -1,
+        numbers: vec![],
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["name", "age", "numbers"], fields(&errors));
+    assert_eq!(
+        vec![
+            "length must be at most 8, got 14",
+            "must be at least 0, got -1",
+            "must not be empty"
+        ],
+        errors.iter().map(|e| e.reason.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["length.max", "range.min", "non_empty"],
+        errors
+            .iter()
+            .map(sealedstruct::ValidationError::code)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![("max", "8"), ("actual", "14")],
+        errors.iter().next().unwrap().params().collect::<Vec<_>>()
+    );
+
+    let errors = UserRaw {
+        name: "admin".into(),
+        age: 101,
+        numbers: vec![NumbersRaw {
+            int8: 127,
+            ..Default::default()
+        }],
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["name", "age", "numbers[0].int8"], fields(&errors));
+}
+
+#[test]
+fn setters_run_handwritten_try_into_nested() {
+    let numbers = NumbersRaw {
+        int8: 1,
+        ..Default::default()
+    }
+    .seal()
+    .unwrap();
+    assert_eq!(99, numbers.clone().with_int8(99).unwrap().int8);
+    assert_eq!(
+        invalid_numbers().seal().unwrap_err(),
+        numbers.with_int8(127).unwrap_err()
+    );
+}
+
+/// Fields named like the locals of the generated code
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(check = Self::validate)]
+pub struct ShadowingRaw {
+    #[sealed(non_empty)]
+    pub check: String,
+    #[sealed(range(max = 10))]
+    pub rules: i32,
+    pub nested: i32,
+    pub fields: i32,
+    pub e: i32,
+    pub raw: i32,
+}
+
+/// Fields named like the locals of the generated setters, without cross-field checks
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub struct SetterShadowingRaw {
+    #[sealed(range(max = 10))]
+    pub raw: i32,
+    pub check: i32,
+    pub sealed: i32,
+    pub nested: i32,
+}
+
+impl ShadowingRaw {
+    fn validate(_: &ShadowingRaw) -> sealedstruct::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn fields_named_like_generated_locals() {
+    let raw = || ShadowingRaw {
+        check: "".into(),
+        rules: 11,
+        nested: 0,
+        fields: 0,
+        e: 0,
+        raw: 0,
+    };
+    assert_eq!(
+        vec!["check", "rules"],
+        fields(&raw().check_sealable().unwrap_err())
+    );
+    assert_eq!(raw(), raw().try_seal().unwrap_err().raw);
+    assert_eq!(vec!["check", "rules"], fields(&raw().seal().unwrap_err()));
+
+    let sealed = ShadowingRaw {
+        check: "check".into(),
+        rules: 1,
+        ..raw()
+    }
+    .seal()
+    .unwrap();
+    let sealed = sealed.with_raw(2).unwrap();
+    assert_eq!(2, sealed.raw);
+    assert_eq!(vec!["rules"], fields(&sealed.with_rules(11).unwrap_err()));
+
+    let sealed = SetterShadowingRaw {
+        raw: 1,
+        check: 2,
+        sealed: 3,
+        nested: 4,
+    }
+    .seal()
+    .unwrap();
+    let updated = sealed.with_raw(5).unwrap().with_sealed(6).unwrap();
+    assert_eq!(
+        (5, 2, 6, 4),
+        (updated.raw, updated.check, updated.sealed, updated.nested)
+    );
+    assert_eq!(vec!["raw"], fields(&updated.with_raw(11).unwrap_err()));
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(check = Self::validate)]
+pub struct RangeRaw {
+    #[sealed(range(min = 0))]
+    pub from: i32,
+    pub to: i32,
+}
+
+impl RangeRaw {
+    fn validate(range: &RangeRaw) -> sealedstruct::Result<()> {
+        if range.from < range.to {
+            Ok(())
+        } else {
+            sealedstruct::ValidationError::on_fields("from", ["to"], "from must be smaller than to")
+                .into()
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub struct ScheduleRaw {
+    pub range: RangeRaw,
+}
+
+#[test]
+fn cross_field_check() {
+    assert!(RangeRaw { from: 1, to: 2 }.seal().is_ok());
+
+    let errors = ScheduleRaw {
+        range: RangeRaw { from: 2, to: 1 },
+    }
+    .seal()
+    .unwrap_err();
+    let error = errors.into_iter().next().expect("One error");
+    assert_eq!("from must be smaller than to", error.reason);
+    assert_eq!(
+        vec!["range.from", "range.to"],
+        error.iter_fields().collect::<Vec<_>>()
+    );
+
+    // Checking by reference runs the cross-field checks as well
+    let range = RangeRaw { from: 2, to: 1 };
+    assert_eq!(
+        range.check_sealable().unwrap_err(),
+        range.seal().unwrap_err()
+    );
+
+    // The check only runs if all fields are valid
+    let range = || RangeRaw { from: -2, to: -3 };
+    assert_eq!(
+        range().check_sealable().unwrap_err(),
+        range().try_seal().unwrap_err().errors
+    );
+    let errors = range().seal().unwrap_err();
+    assert_eq!(vec!["from"], fields(&errors));
+}
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(
+    sealed = "Account",
+    inner = "AccountData",
+    result = "AccountChecks",
+    wrapper = "AccountSealed"
+)]
+pub struct AccountDto {
+    #[sealed(non_empty)]
+    pub name: String,
+}
+
+/// Would collide with the default name of the inner type
+pub struct AccountInner;
+
+#[test]
+fn configured_names() {
+    let account: Account = AccountDto { name: "Ada".into() }.seal().unwrap();
+    let data: &AccountData = &account;
+    assert_eq!("Ada", data.name);
+    let _: &AccountSealed<AccountData> = &account;
+    let _ = AccountChecks {
+        name: Ok("".into()),
+    };
+    let _ = AccountInner;
+
+    assert!(AccountDto { name: "".into() }.seal().is_err());
+}
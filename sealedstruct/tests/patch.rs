@@ -0,0 +1,148 @@
+use common::*;
+use sealedstruct::prelude::*;
+
+mod common;
+
+#[derive(
+    PartialEq,
+    Debug,
+    sealedstruct::Nested,
+    sealedstruct::TryIntoNested,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[sealedDerive(Clone)]
+#[sealed(patch(serde))]
+#[serde(rename_all = "camelCase")]
+pub struct AddressRaw {
+    #[sealed(non_empty)]
+    pub city: String,
+    #[sealed(length(min = 5, max = 5))]
+    pub zip_code: String,
+}
+
+#[derive(
+    PartialEq,
+    Debug,
+    sealedstruct::Nested,
+    sealedstruct::TryIntoNested,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[sealedDerive(Clone)]
+#[sealed(patch(serde))]
+pub struct ProfileRaw {
+    #[sealed(length(min = 2))]
+    pub name: String,
+    pub nickname: Option<String>,
+    pub address: AddressRaw,
+    pub numbers: NumbersRaw,
+}
+
+fn profile() -> Profile {
+    ProfileRaw {
+        name: "Ada".into(),
+        nickname: Some("ada".into()),
+        address: AddressRaw {
+            city: "London".into(),
+            zip_code: "12345".into(),
+        },
+        numbers: NumbersRaw::default(),
+    }
+    .seal()
+    .unwrap()
+}
+
+#[test]
+fn apply_patch() {
+    let patched = profile()
+        .apply_patch(ProfilePatch {
+            nickname: Some(None),
+            address: Some(AddressPatch {
+                city: Some("Bonn".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(
+        ProfileRaw {
+            name: "Ada".into(),
+            nickname: None,
+            address: AddressRaw {
+                city: "Bonn".into(),
+                zip_code: "12345".into(),
+            },
+            numbers: NumbersRaw::default(),
+        },
+        patched
+    );
+
+    let patch = || ProfilePatch {
+        name: Some("A".into()),
+        address: Some(AddressPatch {
+            zip_code: Some("1".into()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let errors = profile().apply_patch(patch()).unwrap_err();
+    let mut raw = ProfileRaw::open(profile());
+    raw.patch(patch());
+    assert_eq!(raw.seal().unwrap_err(), errors);
+    assert_eq!(vec!["name", "address.zip_code"], fields(&errors));
+}
+
+#[test]
+fn deserialize_patch() {
+    let patch: ProfilePatch =
+        serde_json::from_str(r#"{"nickname": null, "address": {"zipCode": "54321"}}"#).unwrap();
+    let patched = profile().apply_patch(patch).unwrap();
+    assert_eq!(None, patched.nickname);
+    assert_eq!("London", patched.address.city);
+    assert_eq!("54321", patched.address.zip_code);
+
+    assert!(serde_json::from_str::<ProfilePatch>(r#"{"name": null}"#).is_err());
+    assert_eq!(
+        r#"{"address":{"zipCode":"54321"}}"#,
+        serde_json::to_string(&ProfilePatch {
+            address: Some(AddressPatch {
+                zip_code: Some("54321".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap()
+    );
+}
+
+/// Doesn't implement serde's traits, so the patch of a struct containing it can't derive them
+#[derive(PartialEq, Debug, Clone, sealedstruct::IntoNested)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+#[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealedDerive(Clone)]
+#[sealed(patch)]
+pub struct ThemeRaw {
+    #[sealed(non_empty)]
+    pub name: String,
+    pub accent: Color,
+}
+
+#[test]
+fn patch_without_serde() {
+    let theme = ThemeRaw {
+        name: "dark".into(),
+        accent: Color(0, 0, 0),
+    }
+    .seal()
+    .unwrap();
+    let patched = theme
+        .apply_patch(ThemePatch {
+            accent: Some(Color(255, 0, 0)),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(Color(255, 0, 0), patched.accent);
+    assert_eq!("dark", patched.name);
+}
@@ -1,3 +1,7 @@
+use common::fields;
+
+mod common;
+
 #[test]
 fn sealed_numbers_simple() {
     #[derive(PartialEq, Default, Debug, sealedstruct::Seal)]
@@ -59,4 +63,269 @@ fn generic_constrained() {
     }
     .seal()
     .unwrap_err();
+
+    let error = sealedstruct::TrySealable::try_seal(SimpleGenericRaw {
+        x: 0,
+        inner: "test",
+    })
+    .unwrap_err();
+    assert_eq!("test", error.raw.inner);
+    assert_eq!("Not 42", error.errors.iter().next().unwrap().reason);
+
+    let sealed = SimpleGenericRaw {
+        x: 42,
+        inner: "test",
+    }
+    .seal()
+    .unwrap();
+    let sealed = sealed.with_inner("other").unwrap();
+    assert_eq!("other", sealed.inner);
+    sealed.with_x(0).unwrap_err();
+}
+
+#[test]
+fn enum_with_fields() {
+    #[derive(PartialEq, PartialOrd, Debug, sealedstruct::Seal)]
+    pub struct PercentageRaw(f32);
+    impl sealedstruct::Validator for PercentageRaw {
+        fn check(&self) -> sealedstruct::Result<()> {
+            PercentageResult(if matches!(self.0, 0.0..=1.0) {
+                Ok(())
+            } else {
+                sealedstruct::ValidationError::new("Must be between 0 and 1").into()
+            })
+            .into()
+        }
+    }
+
+    #[derive(PartialEq, Debug, sealedstruct::Seal)]
+    pub enum LimitRaw {
+        Absolute(u32),
+        Relative(Percentage),
+        Range { from: u32, to: u32 },
+        Unlimited,
+    }
+    impl sealedstruct::Validator for LimitRaw {
+        fn check(&self) -> sealedstruct::Result<()> {
+            match self {
+                LimitRaw::Absolute(_) => LimitResult::Absolute(Ok(())),
+                LimitRaw::Relative(x) => LimitResult::Relative(if (**x).0 <= 0.5 {
+                    Ok(())
+                } else {
+                    sealedstruct::ValidationError::new("Must be at most 50%").into()
+                }),
+                LimitRaw::Range { from, to } => LimitResult::Range {
+                    from: Ok(()),
+                    to: if from < to {
+                        Ok(())
+                    } else {
+                        sealedstruct::ValidationError::new("Must be bigger than from").into()
+                    },
+                },
+                LimitRaw::Unlimited => LimitResult::Unlimited,
+            }
+            .into()
+        }
+    }
+
+    let _: Limit = LimitRaw::Absolute(10).seal().unwrap();
+    let _: Limit = LimitRaw::Unlimited.seal().unwrap();
+    let _: Limit = LimitRaw::Relative(PercentageRaw(0.2).seal().unwrap())
+        .seal()
+        .unwrap();
+
+    let relative = LimitRaw::Relative(PercentageRaw(0.8).seal().unwrap())
+        .seal()
+        .unwrap_err();
+    assert_eq!(vec!["Relative.0"], fields(&relative));
+
+    let range = LimitRaw::Range { from: 10, to: 5 }.seal().unwrap_err();
+    assert_eq!(vec!["Range.to"], fields(&range));
+}
+
+#[test]
+fn configured_names() {
+    #[derive(PartialEq, Debug, sealedstruct::Seal)]
+    #[sealed(sealed = "Port", result = "PortChecks", wrapper = "PortSealed")]
+    pub struct PortInput(u16);
+    impl sealedstruct::Validator for PortInput {
+        fn check(&self) -> sealedstruct::Result<()> {
+            PortChecks(if self.0 != 0 {
+                Ok(())
+            } else {
+                sealedstruct::ValidationError::new("Port 0 is reserved").into()
+            })
+            .into()
+        }
+    }
+
+    let port: Port = PortInput(8080).seal().unwrap();
+    let _: &PortSealed<PortInput> = &port;
+    assert_eq!(8080, port.0 .0);
+    PortInput(0).seal().unwrap_err();
+}
+
+mod wrapped {
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, sealedstruct::Seal)]
+    #[sealed(mode = "wrap")]
+    #[sealedDerive(Clone)]
+    pub struct PortRaw(pub u16);
+
+    impl sealedstruct::Validator for PortRaw {
+        fn check(&self) -> sealedstruct::Result<()> {
+            sealedstruct::checks::range(&self.0, Some(1), None)
+        }
+    }
+
+    impl std::fmt::Display for PortRaw {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, ":{}", self.0)
+        }
+    }
+}
+
+/// A field named like the local of the generated setter
+mod shadowing {
+    #[derive(PartialEq, Debug, sealedstruct::Seal)]
+    pub struct CrateRaw {
+        pub raw: u16,
+    }
+
+    impl sealedstruct::Validator for CrateRaw {
+        fn check(&self) -> sealedstruct::Result<()> {
+            sealedstruct::checks::range(&self.raw, None, Some(10))
+        }
+    }
+}
+
+#[test]
+fn setter_of_field_named_like_local() {
+    use shadowing::*;
+
+    let packed = Crate::new(CrateRaw { raw: 1 }).unwrap();
+    assert_eq!(5, packed.with_raw(5).unwrap().raw);
+    let packed = Crate::new(CrateRaw { raw: 1 }).unwrap();
+    assert!(packed.with_raw(11).is_err());
+}
+
+mod nested {
+    #[derive(PartialEq, Debug, sealedstruct::Seal, sealedstruct::TryIntoNested)]
+    #[sealed(mode = "nested")]
+    #[sealedDerive(Clone, Eq, PartialOrd, Ord)]
+    pub struct PortRaw(#[sealed(range(min = 1))] pub u16);
+
+    impl std::fmt::Display for PortInner {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, ":{}", self.0)
+        }
+    }
+}
+
+#[test]
+fn modes_share_surface() {
+    macro_rules! assert_surface {
+        ($mode:ident) => {{
+            use sealedstruct::SealedExtensions;
+            use $mode::{Port, PortRaw};
+
+            let port = Port::new(PortRaw(80)).unwrap();
+            assert_eq!(80, port.0);
+            assert_eq!(":80", port.to_string());
+            assert_eq!(PortRaw(80), PortRaw::from(port.into_inner()));
+
+            assert_eq!(80, sealedstruct::Sealable::seal(PortRaw(80)).unwrap().0);
+            let port: Port = PortRaw(80).seal().unwrap();
+            assert!(port < PortRaw(443).seal().unwrap());
+            assert_eq!(port, port.clone());
+            assert_eq!(80, Port::try_from(PortRaw(80)).unwrap().0);
+            assert!(sealedstruct::Sealable::seal(PortRaw(0)).is_err());
+            assert!(Port::try_from(PortRaw(0)).is_err());
+
+            let port = Port::new(PortRaw(80))
+                .unwrap()
+                .modify(|raw| raw.0 = 443)
+                .unwrap();
+            assert_eq!(443, port.0);
+            let (mut port, errors) = port.modify(|raw| raw.0 = 0).unwrap_err();
+            assert_eq!(443, port.0);
+            assert_eq!(1, errors.iter().count());
+            assert!(port.try_update(|raw| raw.0 = 0).is_err());
+            assert_eq!(443, port.0);
+            port.try_update(|raw| raw.0 = 8080).unwrap();
+            assert_eq!(8080, port.0);
+        }};
+    }
+    assert_surface!(wrapped);
+    assert_surface!(nested);
+}
+
+mod composed {
+    #[derive(PartialEq, Debug, sealedstruct::Seal)]
+    pub struct ShareRaw(pub f32);
+
+    impl sealedstruct::Validator for ShareRaw {
+        fn check(&self) -> sealedstruct::Result<()> {
+            sealedstruct::checks::range(&self.0, Some(0.0), Some(1.0))
+        }
+    }
+
+    #[derive(PartialEq, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+    pub struct BudgetRaw {
+        pub total: u32,
+        pub reserve: ShareRaw,
+        pub spent: Option<ShareRaw>,
+    }
+
+    #[sealedstruct::sealed]
+    #[derive(PartialEq, Debug)]
+    pub struct Allocation {
+        pub share: Share,
+    }
+}
+
+#[test]
+fn wrapped_raw_in_nested() {
+    use composed::*;
+    use sealedstruct::Sealable;
+
+    let raw = || BudgetRaw {
+        total: 100,
+        reserve: ShareRaw(0.1),
+        spent: Some(ShareRaw(0.5)),
+    };
+    let budget = raw().seal().unwrap();
+    let reserve: &Share = &budget.reserve;
+    assert_eq!(0.1, reserve.0);
+    assert_eq!(raw(), budget);
+    assert_eq!(raw(), BudgetRaw::from(budget.into_inner()));
+
+    let errors = BudgetRaw {
+        reserve: ShareRaw(1.5),
+        spent: Some(ShareRaw(-1.0)),
+        ..raw()
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["reserve", "spent"], fields(&errors));
+
+    assert_eq!(
+        ShareRaw(0.3),
+        ShareRaw::open(Sealable::seal(ShareRaw(0.3)).unwrap())
+    );
+    let error = AllocationRaw {
+        share: ShareRaw(2.0),
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["share"], fields(&error));
+}
+
+#[test]
+fn validator_and_checkable_in_scope() {
+    use composed::ShareRaw;
+    use sealedstruct::{Checkable, Validator};
+
+    let raw = ShareRaw(1.5);
+    assert_eq!(raw.check().unwrap_err(), raw.check_sealable().unwrap_err());
+    assert!(ShareRaw(0.5).check().is_ok());
 }
@@ -0,0 +1,133 @@
+use common::fields;
+use model::*;
+use sealedstruct::Sealable;
+
+mod common;
+
+mod model {
+    #[sealedstruct::sealed]
+    #[derive(PartialEq, Debug, Default, Clone)]
+    #[sealedDerive(Clone)]
+    pub struct Point {
+        #[sealed(range(min = 0))]
+        pub x: i32,
+        pub y: i32,
+    }
+
+    #[sealedstruct::sealed(check = Self::validate)]
+    #[derive(PartialEq, Debug)]
+    pub struct Polygon {
+        #[sealed(length(min = 3))]
+        pub points: Vec<Point>,
+        pub name: Option<String>,
+    }
+
+    impl PolygonRaw {
+        fn validate(polygon: &PolygonRaw) -> sealedstruct::Result<()> {
+            if polygon.name.as_deref() == Some("") {
+                sealedstruct::ValidationError::on_field("name", "Name mustn't be empty").into()
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[sealedstruct::sealed]
+    #[derive(PartialEq, Debug)]
+    pub enum Shape {
+        Polygon(Polygon),
+        Circle { center: Point, radius: u32 },
+    }
+}
+
+fn triangle() -> Vec<PointRaw> {
+    vec![PointRaw::default(); 3]
+}
+
+#[test]
+fn generates_raw_types() {
+    let raw = PolygonRaw {
+        points: triangle(),
+        name: Some("triangle".into()),
+    };
+    let polygon: Polygon = raw.seal().unwrap();
+    let first: &Point = &polygon.points[0];
+    assert_eq!(0, first.x);
+
+    let raw = PolygonRaw::open(polygon);
+    assert_eq!(triangle(), raw.points);
+
+    let shape = ShapeRaw::Circle {
+        center: PointRaw { x: 1, y: 1 },
+        radius: 2,
+    }
+    .seal()
+    .unwrap();
+    assert_eq!(
+        ShapeRaw::Circle {
+            center: PointRaw { x: 1, y: 1 },
+            radius: 2,
+        },
+        shape
+    );
+}
+
+#[test]
+fn sealed_derive_applies_to_sealed_type() {
+    let point: Point = PointRaw { x: 1, y: 2 }.seal().unwrap();
+    let copy = point.clone();
+    assert_eq!(point, copy);
+    assert_eq!(
+        "PointWrapper(PointInner { x: 1, y: 2 })",
+        format!("{point:?}")
+    );
+}
+
+#[test]
+fn checks_are_applied_to_raw() {
+    let errors = PolygonRaw {
+        points: vec![PointRaw { x: -1, y: 0 }],
+        name: None,
+    }
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["points", "points[0].x"], fields(&errors));
+
+    let errors = ShapeRaw::Polygon(PolygonRaw {
+        points: triangle(),
+        name: Some("".into()),
+    })
+    .seal()
+    .unwrap_err();
+    assert_eq!(vec!["Polygon.0.name"], fields(&errors));
+}
+
+#[test]
+fn setters_check_again() {
+    let polygon = || -> Polygon {
+        PolygonRaw {
+            points: triangle(),
+            name: None,
+        }
+        .seal()
+        .unwrap()
+    };
+
+    let named = polygon().with_name(Some("triangle".into())).unwrap();
+    assert_eq!(Some("triangle"), named.name.as_deref());
+    // Cross-field checks run again
+    let errors = polygon().with_name(Some("".into())).unwrap_err();
+    assert_eq!(vec!["name"], fields(&errors));
+
+    // Field checks run again as well
+    let square = vec![PointRaw::default(); 4];
+    assert_eq!(4, polygon().with_points(square).unwrap().points.len());
+    let line = vec![PointRaw::default(); 2];
+    let errors = polygon().with_points(line).unwrap_err();
+    assert_eq!(vec!["points"], fields(&errors));
+
+    // Setters take the raw field value, as they seal the whole value again
+    let point = || PointRaw::default().seal().unwrap();
+    assert_eq!(1, point().with_x(1).unwrap().x);
+    assert_eq!(vec!["x"], fields(&point().with_x(-1).unwrap_err()));
+}
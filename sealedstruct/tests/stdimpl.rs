@@ -1,6 +1,9 @@
+use common::fields;
 use sealedstruct::Sealable;
 use std::collections::{HashMap, HashSet};
 
+mod common;
+
 #[derive(
     PartialEq, Eq, Hash, Clone, Default, Debug, sealedstruct::Nested, sealedstruct::TryIntoNested,
 )]
@@ -53,6 +56,52 @@ fn compare_hashset() {
     assert!(!hashset.partial_eq(&sealed_hashset));
 }
 
+// Elements of a set are sealed to themselves, so they can be looked up by the raw value
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Tag(&'static str);
+
+impl Tag {
+    fn validate(&self) -> sealedstruct::Result<()> {
+        sealedstruct::checks::length(&self.0, None, Some(3))
+    }
+}
+
+impl Sealable for Tag {
+    type Target = Self;
+
+    fn seal(self) -> sealedstruct::Result<Self> {
+        self.validate().map(|_| self)
+    }
+
+    fn open(sealed: Self) -> Self {
+        sealed
+    }
+
+    fn partial_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl sealedstruct::Checkable for Tag {
+    fn check_sealable(&self) -> sealedstruct::Result<()> {
+        self.validate()
+    }
+}
+
+#[test]
+fn hashset_errors_name_the_element() {
+    let tags = [Tag("ok"), Tag("too long")]
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let expected = vec![r#"["Tag(\"too long\")"]"#];
+
+    assert_eq!(
+        expected,
+        fields(&sealedstruct::Checkable::check_sealable(&tags).unwrap_err())
+    );
+    assert_eq!(expected, fields(&tags.seal().unwrap_err()));
+}
+
 #[test]
 fn compare_option() {
     let some = Some(FooRaw { x: 42 });
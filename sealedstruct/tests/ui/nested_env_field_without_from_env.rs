@@ -0,0 +1,8 @@
+#[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(env)]
+pub struct FooRaw {
+    pub name: String,
+    pub hosts: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+#[derive(PartialEq, Debug)]
+pub struct Custom;
+
+impl sealedstruct::Sealable for Custom {
+    type Target = Self;
+
+    fn seal(self) -> sealedstruct::Result<Self> {
+        Ok(self)
+    }
+
+    fn open(sealed: Self) -> Self {
+        sealed
+    }
+
+    fn partial_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl sealedstruct::TrySealable for Custom {
+    fn try_seal(self) -> sealedstruct::SealResult<Self> {
+        Ok(self)
+    }
+}
+
+impl sealedstruct::Checkable for Custom {
+    fn check_sealable(&self) -> sealedstruct::Result<()> {
+        Ok(())
+    }
+}
+
+// Without the opt-in, fields needn't implement `Merge`
+#[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub struct FooRaw {
+    pub name: String,
+    pub custom: Custom,
+}
+
+#[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(merge)]
+pub struct BarRaw {
+    pub name: String,
+    pub custom: Custom,
+}
+
+fn main() {}
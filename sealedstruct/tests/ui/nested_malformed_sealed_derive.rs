@@ -0,0 +1,7 @@
+#[derive(sealedstruct::Nested)]
+#[sealedDerive = "Clone"]
+pub struct FooRaw {
+    x: i32,
+}
+
+fn main() {}
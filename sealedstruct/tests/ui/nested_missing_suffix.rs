@@ -0,0 +1,6 @@
+#[derive(sealedstruct::Nested)]
+pub struct Foo {
+    x: i32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(sealedstruct::Nested)]
+#[sealed(patch)]
+pub enum FooRaw {
+    Bar(i32),
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(sealedstruct::Nested)]
+struct FooRaw {
+    x: i32,
+}
+
+fn main() {}
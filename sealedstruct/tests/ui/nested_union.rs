@@ -0,0 +1,6 @@
+#[derive(sealedstruct::Nested)]
+pub union FooRaw {
+    x: i32,
+}
+
+fn main() {}
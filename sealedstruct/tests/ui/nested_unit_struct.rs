@@ -0,0 +1,4 @@
+#[derive(sealedstruct::Nested)]
+pub struct FooRaw;
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(sealedstruct::Seal)]
+pub struct Raw {
+    x: i32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(sealedstruct::Seal)]
+struct FooRaw {
+    x: i32,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[derive(Debug, sealedstruct::Seal)]
+pub struct FooRaw {
+    pub x: i32,
+}
+
+impl sealedstruct::Validator for FooRaw {
+    fn check(&self) -> sealedstruct::Result<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[sealedstruct::sealed]
+pub struct Foo<T> {
+    x: T,
+}
+
+fn main() {}
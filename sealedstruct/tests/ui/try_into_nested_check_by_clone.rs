@@ -0,0 +1,7 @@
+#[derive(Clone, PartialEq, sealedstruct::Nested, sealedstruct::TryIntoNested)]
+#[sealed(check_by_clone)]
+pub struct FooRaw {
+    pub name: String,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+#[derive(PartialEq, Debug)]
+pub struct Custom;
+
+impl sealedstruct::Sealable for Custom {
+    type Target = Self;
+
+    fn seal(self) -> sealedstruct::Result<Self> {
+        Ok(self)
+    }
+
+    fn open(sealed: Self) -> Self {
+        sealed
+    }
+
+    fn partial_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl sealedstruct::Checkable for Custom {
+    fn check_sealable(&self) -> sealedstruct::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+pub struct FooRaw {
+    pub name: String,
+    pub custom: Custom,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[derive(sealedstruct::TryIntoNested)]
+pub struct Foo {
+    x: i32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(sealedstruct::TryIntoNested)]
+#[sealed(validate = Self::validate)]
+pub struct FooRaw {
+    x: i32,
+}
+
+fn main() {}
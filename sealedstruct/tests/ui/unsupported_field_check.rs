@@ -0,0 +1,7 @@
+#[derive(sealedstruct::TryIntoNested)]
+pub struct FooRaw {
+    #[sealed(between(min = 0, max = 1))]
+    x: i32,
+}
+
+fn main() {}
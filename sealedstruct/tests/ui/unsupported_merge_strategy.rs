@@ -0,0 +1,7 @@
+#[derive(sealedstruct::Nested)]
+pub struct FooRaw {
+    #[sealed(merge = "overwrite")]
+    pub hosts: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(sealedstruct::Seal)]
+#[sealed(mode = "inline")]
+pub struct FooRaw {
+    x: i32,
+}
+
+fn main() {}
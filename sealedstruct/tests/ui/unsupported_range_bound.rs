@@ -0,0 +1,7 @@
+#[derive(sealedstruct::TryIntoNested)]
+pub struct FooRaw {
+    #[sealed(range(minimum = 0))]
+    x: i32,
+}
+
+fn main() {}
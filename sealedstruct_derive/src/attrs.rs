@@ -0,0 +1,248 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Expr, Field, LitStr};
+
+/// Options from `#[sealed(...)]` attributes on the struct or enum itself
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    /// `check = Self::validate`: `fn(&Raw) -> sealedstruct::Result<()>`, whose errors are only
+    /// reported if all fields were sealed successfully. Can be repeated.
+    pub checks: Vec<Expr>,
+    /// `sealed = "Foo"`: Defaults to the name of the raw type without its `Raw` suffix
+    pub sealed: Option<Ident>,
+    /// `inner = "FooData"`: Defaults to `{sealed}Inner`
+    pub inner: Option<Ident>,
+    /// `result = "FooChecks"`: Defaults to `{sealed}Result`
+    pub result: Option<Ident>,
+    /// `wrapper = "FooSealed"`: Defaults to `{sealed}Wrapper`
+    pub wrapper: Option<Ident>,
+    /// `mode = "wrap"` or `mode = "nested"`: Defaults to the strategy of the derive
+    pub mode: Option<Mode>,
+    /// `patch`: Generates `{sealed}Patch` with optional fields and `{sealed}::apply_patch`
+    pub patch: bool,
+    /// `patch(serde)`: Also derives `Serialize` and `Deserialize` for `{sealed}Patch`
+    pub patch_serde: bool,
+    /// `env`: Implements `FromEnv`, which requires it for all fields
+    pub env: bool,
+    /// `merge`: Implements `Merge`, which requires it for all fields in `nested` mode
+    pub merge: bool,
+    /// `check_by_clone`: Implements `TrySealable` and `Checkable` with a clone of the raw value,
+    /// for raw types with a handwritten `TryIntoNested`. Only supported in `nested` mode.
+    pub check_by_clone: bool,
+}
+
+/// Strategy used to generate the sealed type
+#[derive(Clone, Copy)]
+pub(crate) enum Mode {
+    /// The sealed type wraps the raw value, which is validated with `Validator::check`
+    Wrap,
+    /// The sealed type wraps a separate `Inner` type, which is created with `TryIntoNested`
+    Nested,
+}
+
+/// Names of all generated types
+pub(crate) struct Names {
+    pub facade: Ident,
+    pub inner: Ident,
+    pub result: Ident,
+    pub wrapper: Ident,
+    pub patch: Ident,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("sealed")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("check") {
+                    result.checks.push(meta.value()?.parse()?);
+                } else if meta.path.is_ident("sealed") {
+                    result.sealed = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("inner") {
+                    result.inner = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("result") {
+                    result.result = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("wrapper") {
+                    result.wrapper = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("patch") {
+                    result.patch = true;
+                    if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|option| {
+                            if option.path.is_ident("serde") {
+                                result.patch_serde = true;
+                                Ok(())
+                            } else {
+                                Err(option.error("expected `serde`"))
+                            }
+                        })?;
+                    }
+                } else if meta.path.is_ident("env") {
+                    result.env = true;
+                } else if meta.path.is_ident("merge") {
+                    result.merge = true;
+                } else if meta.path.is_ident("check_by_clone") {
+                    result.check_by_clone = true;
+                } else if meta.path.is_ident("mode") {
+                    let mode = meta.value()?.parse::<LitStr>()?;
+                    result.mode = Some(match mode.value().as_str() {
+                        "wrap" => Mode::Wrap,
+                        "nested" => Mode::Nested,
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                mode,
+                                "expected \"wrap\" or \"nested\"",
+                            ))
+                        }
+                    });
+                } else {
+                    return Err(meta.error("unsupported container option"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+
+    /// Uses the explicitly configured names and derives the missing ones from the sealed name
+    pub fn names(&self, raw_name: &Ident) -> syn::Result<Names> {
+        let facade = match &self.sealed {
+            Some(sealed) => sealed.clone(),
+            None => Ident::new(&strip_raw_suffix(raw_name)?, raw_name.span()),
+        };
+        let derived = |explicit: &Option<Ident>, suffix: &str| {
+            explicit
+                .clone()
+                .unwrap_or_else(|| format_ident!("{}{}", facade, suffix))
+        };
+        Ok(Names {
+            inner: derived(&self.inner, "Inner"),
+            result: derived(&self.result, "Result"),
+            wrapper: derived(&self.wrapper, "Wrapper"),
+            patch: format_ident!("{}Patch", facade),
+            facade,
+        })
+    }
+}
+
+/// Returns e.g. `Foo` for `FooRaw`
+fn strip_raw_suffix(raw_name: &Ident) -> syn::Result<String> {
+    raw_name
+        .to_string()
+        .strip_suffix("Raw")
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                raw_name,
+                "Struct name must end with 'Raw' or set the name explicitly with #[sealed(sealed = \"...\")]",
+            )
+        })
+}
+
+/// Turns all `#[sealed(...)]` attributes of a field into expressions of type `sealedstruct::Result<()>`
+///
+/// Supported:
+///  - `range(min = 0, max = 100)`: Both bounds are optional
+///  - `length(min = 1, max = 64)`: Both bounds are optional
+///  - `non_empty`
+///  - `custom = path::to_fn`: Called with a reference to the raw value
+///
+/// `merge = "..."` is skipped, as it's handled by [merge_strategy].
+pub(crate) fn field_checks(
+    attrs: &[Attribute],
+    value: &TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut checks = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("sealed")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("range") || meta.path.is_ident("length") {
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|bound| {
+                    let target = if bound.path.is_ident("min") {
+                        &mut min
+                    } else if bound.path.is_ident("max") {
+                        &mut max
+                    } else {
+                        return Err(bound.error("expected `min` or `max`"));
+                    };
+                    *target = Some(bound.value()?.parse::<Expr>()?);
+                    Ok(())
+                })?;
+                let min = option_tokens(min);
+                let max = option_tokens(max);
+                let check = meta.path.get_ident();
+                checks.push(quote! { sealedstruct::checks::#check(&#value, #min, #max) });
+            } else if meta.path.is_ident("non_empty") {
+                checks.push(quote! { sealedstruct::checks::non_empty(&#value) });
+            } else if meta.path.is_ident("custom") {
+                let function = meta.value()?.parse::<Expr>()?;
+                checks.push(quote! { #function(&#value) });
+            } else if meta.path.is_ident("merge") {
+                meta.value()?.parse::<LitStr>()?;
+            } else {
+                return Err(meta.error("unsupported field check"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(checks)
+}
+
+/// Combines all checks from the `#[sealed(...)]` attributes of the field into one `Result<()>`
+pub(crate) fn combined_field_checks(
+    field: &Field,
+    value: &TokenStream,
+) -> syn::Result<Option<TokenStream>> {
+    Ok(field_checks(&field.attrs, value)?
+        .into_iter()
+        .reduce(|acc, check| {
+            quote! {
+                sealedstruct::prelude::ValidationResultExtensions::combine(#acc, #check).map(|_| ())
+            }
+        }))
+}
+
+/// Parses `#[sealed(merge = "replace" | "append" | "deep")]` into a `sealedstruct::merge::Strategy`
+pub(crate) fn merge_strategy(field: &Field) -> syn::Result<Option<TokenStream>> {
+    let mut strategy = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sealed"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("merge") {
+                let value = meta.value()?.parse::<LitStr>()?;
+                let variant = match value.value().as_str() {
+                    "replace" => quote! { Replace },
+                    "append" => quote! { Append },
+                    "deep" => quote! { Deep },
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            value,
+                            "expected \"replace\", \"append\" or \"deep\"",
+                        ))
+                    }
+                };
+                strategy = Some(quote! { sealedstruct::merge::Strategy::#variant });
+            } else if meta.input.peek(syn::Token![=]) {
+                // Field checks are parsed by `field_checks`
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|nested| {
+                    nested.value()?.parse::<Expr>()?;
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(strategy)
+}
+
+fn option_tokens(value: Option<Expr>) -> TokenStream {
+    match value {
+        Some(x) => quote! { Some(#x) },
+        None => quote! { None },
+    }
+}
@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_quote_spanned, Data, DataStruct, DeriveInput, Fields};
+
+/// Implements `FromEnv` for raw structs with named fields and `#[sealed(env)]`
+///
+/// Each field is overridden by the variable `{name}__{FIELD}`. Errors of all fields are combined.
+pub(crate) fn expand(input: &DeriveInput, enabled: bool) -> syn::Result<TokenStream> {
+    if !enabled {
+        return Ok(TokenStream::new());
+    }
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[sealed(env)] is only supported on structs with named fields",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let overrides = fields
+        .named
+        .iter()
+        .flat_map(|field| Some((field, field.ident.as_ref()?)))
+        .map(|(field, ident)| {
+            let ty = &field.ty;
+            // Spanned, so a field without `FromEnv` is reported on its type
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote_spanned!(ty.span()=> #ty: sealedstruct::env::FromEnv));
+            let label = ident.to_string();
+            let variable = ident.unraw().to_string();
+            quote! {
+                let result = sealedstruct::prelude::ValidationResultExtensions::combine(
+                    result,
+                    sealedstruct::prelude::ValidationResultExtensions::prepend_field(
+                        sealedstruct::env::FromEnv::override_from(
+                            &mut self.#ident,
+                            vars,
+                            &sealedstruct::env::join(name, #variable),
+                        ),
+                        #label,
+                    ),
+                )
+                .map(|_| ());
+            }
+        })
+        .collect::<Vec<_>>();
+    let raw_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics sealedstruct::env::FromEnv for #raw_name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn override_from(
+                &mut self,
+                vars: &std::collections::HashMap<String, String>,
+                name: &str,
+            ) -> sealedstruct::Result<()> {
+                let result: sealedstruct::Result<()> = Ok(());
+                #(#overrides)*
+                result
+            }
+        }
+    })
+}
@@ -1,13 +1,17 @@
 use quote::quote;
 use syn::{parse_macro_input, parse_quote, DeriveInput, WhereClause};
 
+use crate::merge::replace_on_merge;
 use crate::nested::add_trait_bounds;
+use crate::patch::replace_on_patch;
 
 pub fn derive_into_nested(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
     let input = parse_macro_input!(input as DeriveInput);
 
     // Used in the quasi-quotation below as `#name`.
+    let patch = replace_on_patch(&input.ident, &input.generics);
+    let merge = replace_on_merge(&input.ident, &input.generics);
     let struct_name = input.ident;
     let sealable_generics =
         add_trait_bounds(input.generics, &[parse_quote!(sealedstruct::Sealable)]);
@@ -37,6 +41,26 @@ pub fn derive_into_nested(input: proc_macro::TokenStream) -> proc_macro::TokenSt
                 std::cmp::PartialEq::eq(&self, &other)
             }
         }
+
+        impl #impl_generics sealedstruct::TrySealable for #struct_name #ty_generics #where_clause {
+            fn try_seal(self) -> sealedstruct::SealResult<Self> {
+                Ok(self)
+            }
+        }
+
+        impl #impl_generics sealedstruct::Checkable for #struct_name #ty_generics #where_clause {
+            fn check_sealable(&self) -> sealedstruct::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl #impl_generics sealedstruct::Sealed for #struct_name #ty_generics #where_clause {
+            type Raw = Self;
+        }
+
+        #patch
+
+        #merge
     };
 
     // Hand the output tokens back to the compiler.
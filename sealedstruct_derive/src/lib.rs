@@ -1,6 +1,14 @@
+use attrs::{ContainerAttrs, Mode};
+use syn::{parse_macro_input, DeriveInput};
+
+mod attrs;
+mod env;
 mod into_nested;
+mod merge;
 mod nested;
+mod patch;
 mod seal;
+mod sealed;
 mod try_into_nested;
 
 /// Generetes several other structs based on {Structname}Raw
@@ -12,18 +20,72 @@ mod try_into_nested;
 ///  - {Structname}Result: Helper which can be used inside TryIntoNested to turn {StructName}Raw
 ///    into Result<{StructName}Sealed, ValidationErrors>. It is private to the file in which
 ///    it is generated on purpose.
-#[proc_macro_derive(Nested, attributes(sealedDerive))]
+///
+/// All names can be set explicitly, which removes the need for the `Raw` suffix:
+/// `#[sealed(sealed = "Foo", inner = "FooData", result = "FooChecks", wrapper = "FooSealed")]`
+///
+/// `#[sealed(patch)]` generates {Structname}Patch for partial updates, where every field is an `Option`
+/// of the patch of its type, and `{Structname}::apply_patch`. Only structs with named fields are supported.
+/// `#[sealed(patch(serde))]` derives `Serialize` and `Deserialize` for the patch as well.
+///
+/// `#[sealed(merge)]` implements `Merge` for {Structname}Raw field by field, so all fields have to implement it.
+/// The strategy of a field can be set with `#[sealed(merge = "replace" | "append" | "deep")]`.
+/// In `wrap` mode, {Structname}Raw is replaced as a whole.
+///
+/// `#[sealed(env)]` implements `sealedstruct::env::FromEnv` for structs with named fields, so their fields
+/// can be overridden by environment variables like `APP__NUMBERS__INT8`. All fields have to implement it.
+///
+/// `#[sealed(check_by_clone)]` implements `TrySealable` and `Checkable` for a {Structname}Raw with a handwritten
+/// `TryIntoNested` by sealing a clone. It conflicts with `derive(TryIntoNested)`, which implements both itself.
+///
+/// Same as `derive(Seal)` with `#[sealed(mode = "nested")]`
+#[proc_macro_derive(Nested, attributes(sealedDerive, sealed))]
 pub fn derive_nested(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    nested::derive_seal(input)
+    derive_sealed(input, Mode::Nested)
 }
-#[proc_macro_derive(Seal)]
+/// Generates {Structname}, {Structname}Wrapper and {Structname}Result based on {Structname}Raw.
+/// The names can be set with `#[sealed(sealed = "Foo", result = "FooChecks", wrapper = "FooSealed")]`
+///
+/// The strategy is selected with `#[sealed(mode = "wrap" | "nested")]`:
+///  - `wrap` (default): {Structname} wraps {Structname}Raw, which is validated by `Validator::check`
+///  - `nested`: Same as `derive(Nested)`, {Structname}Raw has to implement `TryIntoNested`
+///
+/// Both strategies generate `{Structname}::new`, `into_inner`, `{Structname}Raw::seal`, setters taking the raw
+/// field value, `TryFrom<{Structname}Raw>`, `Sealable` for {Structname}Raw, the comparison and hashing traits,
+/// `Display` and serde support for the sealed type.
+/// {Structname}Raw has to implement `PartialEq`, which `Sealable` uses to compare raw and sealed values.
+/// `#[sealedDerive(...)]` adds derives to {Structname}Inner. In `wrap` mode, they are added to {Structname},
+/// which forwards the derives of {Structname}Raw anyway.
+#[proc_macro_derive(Seal, attributes(sealedDerive, sealed))]
 pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    seal::derive_seal(input)
+    derive_sealed(input, Mode::Wrap)
+}
+
+fn derive_sealed(input: proc_macro::TokenStream, default_mode: Mode) -> proc_macro::TokenStream {
+    // Parse the input tokens into a syntax tree.
+    let input = parse_macro_input!(input as DeriveInput);
+    ContainerAttrs::parse(&input.attrs)
+        .and_then(|container| match container.mode.unwrap_or(default_mode) {
+            Mode::Wrap => seal::expand(input, container),
+            Mode::Nested => nested::expand(input, container),
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 /// Generates a TryIntoNested implementation by forwarding all errors from subfields.
-/// All subfields therefore have to implement TryIntoNested
-#[proc_macro_derive(TryIntoNested)]
+/// All subfields therefore have to implement TryIntoNested, as well as `TrySealable` and `Checkable`
+/// for the implementations of these traits
+///
+/// Fields can be checked declaratively before they are sealed:
+///  - `#[sealed(range(min = 0, max = 100))]`: Both bounds are optional
+///  - `#[sealed(length(min = 1, max = 64))]`: Strings, Vecs, HashMaps and HashSets
+///  - `#[sealed(non_empty)]`
+///  - `#[sealed(custom = path::to_fn)]`: `fn(&T) -> sealedstruct::Result<()>`
+///
+/// Rules spanning multiple fields are declared on the type with `#[sealed(check = Self::validate)]`.
+/// The function receives `&{Structname}Raw`. Its errors are only reported, if all fields were sealed successfully.
+#[proc_macro_derive(TryIntoNested, attributes(sealed))]
 pub fn derive_try_into_nested(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     try_into_nested::derive_try_into_nested(input)
 }
@@ -33,3 +95,18 @@ pub fn derive_try_into_nested(input: proc_macro::TokenStream) -> proc_macro::Tok
 pub fn derive_into_nested(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     into_nested::derive_into_nested(input)
 }
+
+/// Generates {Structname}Raw from the definition of the sealed type and derives `Nested` and
+/// `TryIntoNested` for it. The raw type of each field is taken from `sealedstruct::Sealed`.
+///
+/// All attributes, including `#[sealed(...)]` field checks, are applied to the raw type. This includes derives,
+/// so derives of the sealed type are listed in `#[sealedDerive(...)]`. It implements `Debug` and `PartialEq` anyway.
+/// Arguments are forwarded as container options, e.g. `#[sealedstruct::sealed(check = Self::validate)]`.
+/// Always use the full path, as `sealed` would otherwise be ambiguous with the helper attribute.
+#[proc_macro_attribute]
+pub fn sealed(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    sealed::sealed(args, input)
+}
@@ -0,0 +1,86 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_quote_spanned, Data, DeriveInput, Generics, Index};
+
+use crate::attrs::merge_strategy;
+use crate::nested::all_fields;
+
+/// Implements `Merge` for the raw type of `Nested` with `#[sealed(merge)]`
+///
+/// Structs are merged field by field with the strategy of `#[sealed(merge = "...")]`,
+/// or the default strategy of the field type. Enums are replaced.
+pub(crate) fn expand(input: &DeriveInput, enabled: bool) -> syn::Result<TokenStream> {
+    if !enabled {
+        // Strategies are parsed anyway, so a typo isn't ignored silently
+        for field in all_fields(&input.data) {
+            merge_strategy(field)?;
+        }
+        return Ok(TokenStream::new());
+    }
+    let raw_name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Ok(replace_on_merge(raw_name, &input.generics)),
+    };
+
+    let mut generics = input.generics.clone();
+    let mut merges = Vec::new();
+    let mut supplied = Vec::new();
+    for (position, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => ident.to_token_stream(),
+            None => Index::from(position).to_token_stream(),
+        };
+        let ty = &field.ty;
+        let label = member.to_string();
+        // Spanned, so a field without `Merge` is reported on its type
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote_spanned!(ty.span()=> #ty: sealedstruct::Merge));
+        merges.push(match merge_strategy(field)? {
+            Some(strategy) => quote! {
+                sealedstruct::Merge::merge_with(&mut self.#member, other.#member, #strategy);
+            },
+            None => quote! {
+                sealedstruct::Merge::merge(&mut self.#member, other.#member);
+            },
+        });
+        supplied.push(quote! {
+            supplied.extend(sealedstruct::merge::prepend_field(
+                sealedstruct::Merge::supplied(&self.#member),
+                #label,
+            ));
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics sealedstruct::Merge for #raw_name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn merge(&mut self, other: Self) {
+                #(#merges)*
+            }
+
+            fn supplied(&self) -> Vec<sealedstruct::FieldPath> {
+                #[allow(unused_mut)]
+                let mut supplied = Vec::new();
+                #(#supplied)*
+                supplied
+            }
+        }
+    })
+}
+
+/// Merging types without fields, or whose fields aren't sealed separately, replaces the whole value
+pub(crate) fn replace_on_merge(name: &Ident, generics: &Generics) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics sealedstruct::Merge for #name #ty_generics #where_clause {
+            fn merge(&mut self, other: Self) {
+                *self = other;
+            }
+        }
+    }
+}
@@ -1,42 +1,51 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::{quote, quote_spanned, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
+
+use crate::attrs::{ContainerAttrs, Names};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
 use syn::{
-    parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics, Index, TypeParamBound,
-    Visibility, WhereClause,
+    parse_quote, parse_quote_spanned, Data, DataStruct, DeriveInput, Field, Fields, Generics,
+    Index, Path, Token, TypeParamBound, Visibility, WhereClause,
 };
 
-pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    // Parse the input tokens into a syntax tree.
-    let input = parse_macro_input!(input as DeriveInput);
-    let mut attrs = input.attrs.iter().filter(|x| match x.path().get_ident() {
-        Some(ident) => ident == "sealedDerive",
-        None => false,
-    });
-    let inner_derive = if let Some(x) = attrs.next() {
-        let token_list = x
-            .meta
-            .require_list()
-            .expect("Only supports list attributes");
-        let token = &token_list.tokens;
-
+pub(crate) fn expand(input: DeriveInput, container: ContainerAttrs) -> syn::Result<TokenStream> {
+    ensure_supported(&input)?;
+    ensure_not_private(&input)?;
+    let sealed_derives = sealed_derives(&input)?;
+    let inner_derive = if sealed_derives.is_empty() {
+        TokenStream::new()
+    } else {
         quote! {
-            #[derive(#token)]
+            #[derive(#(#sealed_derives),*)]
         }
+    };
+
+    let Names {
+        facade: facade_name,
+        wrapper: wrapper_name,
+        inner: inner_name,
+        result: result_name,
+        patch: patch_name,
+    } = container.names(&input.ident)?;
+    let patch = crate::patch::expand(
+        &input,
+        container.patch,
+        container.patch_serde,
+        &facade_name,
+        &patch_name,
+    )?;
+    let merge = crate::merge::expand(&input, container.merge)?;
+    let from_env = crate::env::expand(&input, container.env)?;
+    let check_by_clone = if container.check_by_clone {
+        create_check_by_clone(&input.ident, &input.generics, &wrapper_name)
     } else {
         TokenStream::new()
     };
 
-    if let syn::Visibility::Inherited = input.vis {
-        panic!("Raw-Struct mustn't be private. Deriving 'Seal' only makes sense if generated Sealed* is in submodule");
-    }
-
     // Used in the quasi-quotation below as `#name`.
     let raw_name = input.ident;
-    let raw_name_str = raw_name.to_string();
-    if !raw_name_str.ends_with("Raw") {
-        panic!("Struct name must end with 'Raw'");
-    }
 
     let sealable_generics = add_trait_bounds(
         input.generics.clone(),
@@ -45,12 +54,6 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let create_inner_generics = sealable_generics.clone();
     let (impl_generics, ty_generics, where_clause) = sealable_generics.split_for_impl();
 
-    let struct_name_str = &raw_name_str[..(raw_name_str.len() - 3)];
-    let facade_name = syn::Ident::new(struct_name_str, raw_name.span());
-    let wrapper_name = syn::Ident::new(&format!("{struct_name_str}Wrapper"), raw_name.span());
-    let inner_name = syn::Ident::new(&format!("{struct_name_str}Inner"), raw_name.span());
-    let result_name = syn::Ident::new(&format!("{struct_name_str}Result"), raw_name.span());
-
     // Generate an expression to sum up the heap size of each field.
     let inner = create_inner(&input.data, &inner_name, create_inner_generics, &input.vis);
     let result = create_result(
@@ -60,16 +63,53 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let result_into_inner = create_result_into_inner_body(&input.data, &inner_name, &result_name);
     let inner_into_raw = create_inner_into_raw_body(&input.data, &inner_name, &raw_name);
     let cmp_body = create_cmp_raw_with_inner_body(&input.data, &raw_name, &inner_name);
+    let diff_body = create_diff_body(&input.data, &raw_name, &inner_name);
+    // Handwritten `TryIntoNested` and cross-field checks need the raw value, so it is opened and sealed again as a whole
+    let setters = create_setters(
+        &input.data,
+        "Replaces the sealed field and seals the value again, running all checks",
+        |field, ident, setter| {
+            let ty = &field.ty;
+            let raw = local("raw");
+            Ok(quote! {
+                fn #setter(self, #ident: #ty) -> sealedstruct::Result<Self> {
+                    let mut #raw: #raw_name #ty_generics = self.0.into();
+                    #raw.#ident = #ident;
+                    sealedstruct::Sealable::seal(#raw)
+                }
+            })
+        },
+    )?;
+    let wrapper = create_wrapper(&wrapper_name, &[]);
     let input_vis = input.vis;
 
     #[cfg(feature = "serde")]
-    let serde_wrapper = quote! {
-        impl<T: serde::Serialize> serde::Serialize for #wrapper_name<T> {
-            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer,
-            {
-                self.0.serialize(serializer)
+    let serde_wrapper = {
+        // Deserializes the raw type and seals it afterwards
+        let mut de_generics = sealable_generics.clone();
+        de_generics.params.insert(0, parse_quote!('de));
+        de_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#raw_name #ty_generics: serde::Deserialize<'de>));
+        let (de_impl_generics, _, de_where_clause) = de_generics.split_for_impl();
+        quote! {
+            impl<T: serde::Serialize> serde::Serialize for #wrapper_name<T> {
+                fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    self.0.serialize(serializer)
+                }
+            }
+            impl #de_impl_generics serde::Deserialize<'de> for #facade_name #ty_generics #de_where_clause {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let raw = <#raw_name #ty_generics as serde::Deserialize>::deserialize(deserializer)?;
+                    Self::new(raw).map_err(<D::Error as serde::de::Error>::custom)
+                }
             }
         }
     };
@@ -84,10 +124,17 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
         #serde_wrapper
 
+        #patch
+
+        #merge
+
+        #from_env
+
+        #check_by_clone
+
         #input_vis type #facade_name #ty_generics  = #wrapper_name<#inner_name #ty_generics>;
 
-        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
-        pub struct #wrapper_name<T>(T);
+        #wrapper
 
         impl #impl_generics #facade_name #ty_generics #where_clause {
             pub fn new<TRaw: sealedstruct::TryIntoNested<Target = #inner_name #ty_generics>>(raw: TRaw) -> sealedstruct::Result<Self> {
@@ -96,6 +143,28 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             pub fn into_inner(self) -> #inner_name #ty_generics {
                 self.0
             }
+
+            #setters
+        }
+
+        impl #impl_generics #raw_name #ty_generics #where_clause {
+            pub fn seal(self) -> sealedstruct::Result<#facade_name #ty_generics> {
+                sealedstruct::Sealable::seal(self)
+            }
+        }
+
+        impl #impl_generics TryFrom<#raw_name #ty_generics> for #facade_name #ty_generics #where_clause {
+            type Error = sealedstruct::ValidationErrors;
+
+            fn try_from(value: #raw_name #ty_generics) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl<T: std::fmt::Display> std::fmt::Display for #wrapper_name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
         }
 
         impl<T> std::ops::Deref for #wrapper_name<T> {
@@ -111,7 +180,7 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             type Target = #facade_name #ty_generics;
 
             fn seal(self) -> sealedstruct::Result<Self::Target> {
-                Self::Target::new(self)
+                sealedstruct::TryIntoNested::try_into_nested(self).map(#wrapper_name)
             }
 
             fn open(sealed: Self::Target) -> Self {
@@ -121,10 +190,19 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             fn partial_eq(&self, other: &Self::Target) -> bool {
                 self.eq(&other.0)
             }
+
+            fn diff(&self, other: &Self::Target) -> Vec<sealedstruct::FieldChange> {
+                let other = &other.0;
+                #diff_body
+            }
         }
 
 
-         impl #impl_generics From<#inner_name #ty_generics> for #raw_name #ty_generics {
+        impl #impl_generics sealedstruct::Sealed for #facade_name #ty_generics #where_clause {
+            type Raw = #raw_name #ty_generics;
+        }
+
+        impl #impl_generics From<#inner_name #ty_generics> for #raw_name #ty_generics {
             fn from(input: #inner_name #ty_generics) -> Self {
                 #inner_into_raw
             }
@@ -148,8 +226,169 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         }
     };
 
-    // Hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    Ok(expanded)
+}
+
+/// `TrySealable` and `Checkable` for a handwritten `TryIntoNested`, which can only seal by value
+///
+/// `try_seal` keeps a clone to return on error, `check_sealable` seals a clone.
+fn create_check_by_clone(
+    raw_name: &Ident,
+    generics: &Generics,
+    wrapper_name: &Ident,
+) -> TokenStream {
+    let mut generics = add_trait_bounds(generics.clone(), &[parse_quote!(sealedstruct::Sealable)]);
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote_spanned!(raw_name.span()=> Self: std::clone::Clone));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics sealedstruct::TrySealable for #raw_name #ty_generics #where_clause {
+            fn try_seal(self) -> sealedstruct::SealResult<Self> {
+                let raw = std::clone::Clone::clone(&self);
+                match sealedstruct::TryIntoNested::try_into_nested(self) {
+                    Ok(inner) => Ok(#wrapper_name(inner)),
+                    Err(errors) => Err(sealedstruct::SealError::new(errors, raw)),
+                }
+            }
+        }
+
+        impl #impl_generics sealedstruct::Checkable for #raw_name #ty_generics #where_clause {
+            fn check_sealable(&self) -> sealedstruct::Result<()> {
+                sealedstruct::TryIntoNested::try_into_nested(std::clone::Clone::clone(self)).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Name of a local variable in generated code
+///
+/// Its mixed-site span keeps it apart from the bindings, which are named after the fields.
+pub(crate) fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// Generates a setter like `with_foo` for each named field with `setter(field, foo, with_foo)`
+///
+/// Setters share the visibility of their field.
+pub(crate) fn create_setters(
+    data: &Data,
+    doc: &str,
+    setter: impl Fn(&Field, &Ident, &Ident) -> syn::Result<TokenStream>,
+) -> syn::Result<TokenStream> {
+    let fields = match data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => return Ok(TokenStream::new()),
+    };
+    fields
+        .named
+        .iter()
+        .flat_map(|field| Some((field, field.ident.as_ref()?)))
+        .map(|(field, ident)| {
+            let name = format_ident!("with_{}", ident.unraw());
+            let vis = &field.vis;
+            let body = setter(field, ident, &name)?;
+            Ok(quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                #vis #body
+            })
+        })
+        .collect()
+}
+
+/// Rejects inputs, for which no sealed type can be generated
+pub(crate) fn ensure_supported(input: &DeriveInput) -> syn::Result<()> {
+    match &input.data {
+        Data::Union(data) => Err(syn::Error::new_spanned(
+            data.union_token,
+            "Unions are not supported",
+        )),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Unit structs are not supported",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Derives of `#[sealedDerive(...)]`, which apply to the sealed type instead of the raw type
+pub(crate) fn sealed_derives(input: &DeriveInput) -> syn::Result<Vec<Path>> {
+    let mut derives = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sealedDerive"))
+    {
+        let list = attr.meta.require_list().map_err(|_| {
+            syn::Error::new_spanned(
+                attr,
+                "Expected a list of derives, e.g. #[sealedDerive(Clone)]",
+            )
+        })?;
+        derives.extend(list.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?);
+    }
+    Ok(derives)
+}
+
+/// Derives of the wrapper, which forward the traits of the wrapped type
+const WRAPPER_DERIVES: [&str; 9] = [
+    "Copy",
+    "Clone",
+    "Debug",
+    "PartialEq",
+    "Eq",
+    "Hash",
+    "Default",
+    "Ord",
+    "PartialOrd",
+];
+
+/// Implemented by hand for the wrapper, see `serde_wrapper`
+const WRAPPER_SERDE: [&str; 2] = ["Serialize", "Deserialize"];
+
+/// The wrapper, which is the sealed type of both modes, with additional `derives` it doesn't have already
+pub(crate) fn create_wrapper(wrapper_name: &Ident, derives: &[Path]) -> TokenStream {
+    let implemented = |name: &Ident| {
+        WRAPPER_DERIVES.iter().any(|derive| name == derive)
+            || (cfg!(feature = "serde") && WRAPPER_SERDE.iter().any(|derive| name == derive))
+    };
+    let derives = WRAPPER_DERIVES
+        .iter()
+        .map(|name| Ident::new(name, Span::call_site()).into())
+        .chain(
+            derives
+                .iter()
+                .filter(|path| {
+                    !path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| implemented(&segment.ident))
+                })
+                .cloned(),
+        )
+        .collect::<Vec<Path>>();
+    quote! {
+        #[derive(#(#derives),*)]
+        pub struct #wrapper_name<T>(T);
+    }
+}
+
+pub(crate) fn ensure_not_private(input: &DeriveInput) -> syn::Result<()> {
+    match input.vis {
+        Visibility::Inherited => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Raw-Struct mustn't be private. Deriving 'Seal' only makes sense if generated Sealed* is in submodule",
+        )),
+        _ => Ok(()),
+    }
 }
 
 fn create_cmp_raw_with_inner_body(
@@ -174,26 +413,192 @@ fn create_cmp_raw_with_inner_body(
         Data::Enum(ref e) => {
             let field_mappings = e.variants.iter().map(|v| {
                 let ident = &v.ident;
-                match &v.fields {
-                    &Fields::Unit => {
-                        quote! {
-                            &#raw_name::#ident => &other == &&#inner_name::#ident,
-                        }
-                    }
-                    _ => unimplemented!("Just unit fields are supported"),
+                let bindings = variant_bindings(&v.fields);
+                let self_pattern = variant_pattern(&v.fields, &bindings, "self_");
+                let other_pattern = variant_pattern(&v.fields, &bindings, "other_");
+                let cmp = bindings.iter().fold(quote! { true }, |acc, (_, binding)| {
+                    let self_binding = format_ident!("self_{}", binding);
+                    let other_binding = format_ident!("other_{}", binding);
+                    quote!( #acc && sealedstruct::Sealable::partial_eq(#self_binding, #other_binding))
+                });
+                quote! {
+                    (#raw_name::#ident #self_pattern, #inner_name::#ident #other_pattern) => #cmp,
                 }
             });
 
             quote! {
-                match self {
+                #[allow(unreachable_patterns)]
+                match (self, other) {
                     #(#field_mappings)*
+                    _ => false,
                 }
             }
-        } // Todo: Wrong, but compiles...
-        Data::Union(_) => unimplemented!(),
+        }
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 
+/// Concatenates the changes of all fields, labeled like their errors. Different variants are a change of the value itself.
+fn create_diff_body(data: &Data, raw_name: &Ident, inner_name: &Ident) -> TokenStream {
+    let concat = |fields: Vec<(TokenStream, TokenStream, String)>| {
+        let changes = fields.into_iter().map(|(raw, sealed, label)| {
+            quote! {
+                changes.extend(sealedstruct::diff::prepend_field(
+                    sealedstruct::Sealable::diff(#raw, #sealed),
+                    #label,
+                ));
+            }
+        });
+        quote! {
+            {
+                #[allow(unused_mut)]
+                let mut changes = Vec::new();
+                #(#changes)*
+                changes
+            }
+        }
+    };
+    match data {
+        Data::Struct(data) => concat(
+            variant_bindings(&data.fields)
+                .into_iter()
+                .map(|(member, _)| {
+                    (
+                        quote! { &self.#member },
+                        quote! { &other.#member },
+                        member.to_string(),
+                    )
+                })
+                .collect(),
+        ),
+        Data::Enum(e) => {
+            let variants = e.variants.iter().map(|v| {
+                let ident = &v.ident;
+                let ident_str = ident.to_string();
+                let bindings = variant_bindings(&v.fields);
+                let self_pattern = variant_pattern(&v.fields, &bindings, "self_");
+                let other_pattern = variant_pattern(&v.fields, &bindings, "other_");
+                let changes = concat(
+                    bindings
+                        .iter()
+                        .map(|(member, binding)| {
+                            let self_binding = format_ident!("self_{}", binding);
+                            let other_binding = format_ident!("other_{}", binding);
+                            (
+                                self_binding.to_token_stream(),
+                                other_binding.to_token_stream(),
+                                member.to_string(),
+                            )
+                        })
+                        .collect(),
+                );
+                quote! {
+                    (#raw_name::#ident #self_pattern, #inner_name::#ident #other_pattern) => {
+                        sealedstruct::diff::prepend_variant(#changes, #ident_str)
+                    }
+                }
+            });
+            quote! {
+                #[allow(unreachable_patterns)]
+                match (self, other) {
+                    #(#variants)*
+                    _ => vec![sealedstruct::FieldChange::new(sealedstruct::ChangeKind::Changed)],
+                }
+            }
+        }
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
+    }
+}
+
+/// Fields of the struct or of all variants
+pub(crate) fn all_fields(data: &Data) -> Vec<&Field> {
+    match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        Data::Union(_) => Vec::new(),
+    }
+}
+
+/// Returns the member and the name of a local binding for each field of a struct or variant
+pub(crate) fn variant_bindings(fields: &Fields) -> Vec<(TokenStream, Ident)> {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .flat_map(|f| f.ident.clone())
+            .map(|ident| (ident.to_token_stream(), ident))
+            .collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|f| (Index::from(f).to_token_stream(), format_ident!("x{}", f)))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    }
+}
+
+/// Generates e.g. `{ foo: prefix_foo, bar: prefix_bar }` or `(prefix_x0, prefix_x1)`
+pub(crate) fn variant_pattern(
+    fields: &Fields,
+    bindings: &[(TokenStream, Ident)],
+    prefix: &str,
+) -> TokenStream {
+    let bound = bindings
+        .iter()
+        .map(|(member, binding)| (member, format_ident!("{}{}", prefix, binding)));
+    match fields {
+        Fields::Named(_) if prefix.is_empty() => {
+            let bound = bindings.iter().map(|(_, binding)| binding);
+            quote! { { #(#bound),* } }
+        }
+        Fields::Named(_) => {
+            let bound = bound.map(|(member, binding)| quote! { #member: #binding });
+            quote! { { #(#bound),* } }
+        }
+        Fields::Unnamed(_) => {
+            let bound = bound.map(|(_, binding)| binding);
+            quote! { ( #(#bound),* ) }
+        }
+        Fields::Unit => TokenStream::new(),
+    }
+}
+
+/// Combines the results of all fields, prefixing each error with the fields label
+///
+/// Generates e.g. the pattern `((foo, bar), baz)` and the expression
+/// `input.foo.prepend_field("foo").combine(input.bar.prepend_field("bar")).combine(input.baz.prepend_field("baz"))`
+pub(crate) fn combine_fields(
+    fields: impl IntoIterator<Item = (TokenStream, Ident, String)>,
+) -> Option<(TokenStream, TokenStream)> {
+    combine_fields_with(
+        &quote! { sealedstruct::prelude::ValidationResultExtensions },
+        fields,
+    )
+}
+
+/// Like [combine_fields], but calls `combine` and `prepend_field` of another extension trait
+pub(crate) fn combine_fields_with(
+    extensions: &TokenStream,
+    fields: impl IntoIterator<Item = (TokenStream, Ident, String)>,
+) -> Option<(TokenStream, TokenStream)> {
+    let mut iter = fields.into_iter();
+    let (first_acc, first_var, first_label) = iter.next()?;
+    Some(iter.fold(
+        (
+            first_var.to_token_stream(),
+            quote! {
+                #extensions::prepend_field(#first_acc, #first_label)
+            },
+        ),
+        |(fields_list, assign), (next_acc, next_var, next_label)| {
+            (
+                quote! {(#fields_list, #next_var)},
+                quote! { #extensions::combine(#assign,
+                    #extensions::prepend_field(#next_acc, #next_label))
+                },
+            )
+        },
+    ))
+}
+
 fn create_result_into_inner_body(
     data: &Data,
     inner_name: &Ident,
@@ -207,32 +612,17 @@ fn create_result_into_inner_body(
                         .named
                         .iter()
                         .map(|f| f.ident.clone().into_token_stream());
-                    let mut ident_iter = fields.named.iter().flat_map(|f| f.ident.clone());
-                    let field_list = match ident_iter.next() {
-                        Some(first) => {
-                            let first_string = first.to_string();
-                            let (fields, assign) = ident_iter.fold(
-                                (first.to_token_stream(), quote!{
-                                    sealedstruct::prelude::ValidationResultExtensions::prepend_path(input.#first, #first_string)
-                                }),
-                                |(fields_list, assign), next| {
-                                    let next_text = next.to_string();
-                                    (
-                                        quote! {(#fields_list, #next)},
-                                        quote! { sealedstruct::prelude::ValidationResultExtensions::combine(#assign,
-                                            sealedstruct::prelude::ValidationResultExtensions::prepend_path(input.#next, #next_text))
-                                        },
-                                    )
-                                },
-                            );
-                            // Generates e.g.:
-                            // let ((foo, bar), baz) = input.foo.combine(input.bar).combine(input.baz)?;
-                            quote! {
-                                let #fields = #assign?;
-                            }
+                    let field_list = combine_fields(fields.named.iter().flat_map(|f| {
+                        let ident = f.ident.clone()?;
+                        Some((quote! { input.#ident }, ident.clone(), ident.to_string()))
+                    }))
+                    .map(|(fields, assign)| {
+                        // Generates e.g.:
+                        // let ((foo, bar), baz) = input.foo.combine(input.bar).combine(input.baz)?;
+                        quote! {
+                            let #fields = #assign?;
                         }
-                        _ => TokenStream::new(),
-                    };
+                    });
                     quote! {
                         #field_list
                         Ok(#inner_name { #(#field_idents,)* })
@@ -241,37 +631,19 @@ fn create_result_into_inner_body(
                 Fields::Unnamed(ref fields) => {
                     let field_idents = (0..fields.unnamed.len())
                         .map(|f| Ident::new(&format!("x{f}"), Span::call_site()));
-                    let mut ident_iter = (0..fields.unnamed.len()).map(|f| {
+                    let field_list = combine_fields((0..fields.unnamed.len()).map(|f| {
                         let index = Index::from(f);
                         (
                             quote! { input.#index },
                             Ident::new(&format!("x{f}"), Span::call_site()),
                             f.to_string(),
                         )
-                    });
-                    let field_list = match ident_iter.next() {
-                        Some((first_acc, first_var, first_label)) => {
-                            let (fields, assign) = ident_iter.fold(
-                                (first_var.to_token_stream(), quote!{
-                                    sealedstruct::prelude::ValidationResultExtensions::prepend_path(#first_acc, #first_label)
-                                }),
-                                |(fields_list, assign), (next_acc, next_var, next_label)| {
-                                    (
-                                        quote! {(#fields_list, #next_var)},
-                                        quote! { sealedstruct::prelude::ValidationResultExtensions::combine(#assign,
-                                            sealedstruct::prelude::ValidationResultExtensions::prepend_path(#next_acc, #next_label))
-                                        },
-                                    )
-                                },
-                            );
-                            // Generates e.g.:
-                            // let ((foo, bar), baz) = input.foo.combine(input.bar).combine(input.baz)?;
-                            quote! {
-                                let #fields = #assign?;
-                            }
+                    }))
+                    .map(|(fields, assign)| {
+                        quote! {
+                            let #fields = #assign?;
                         }
-                        _ => TokenStream::new(),
-                    };
+                    });
                     quote! {
                         #field_list
                         Ok(#inner_name(#(#field_idents,)* ))
@@ -286,11 +658,28 @@ fn create_result_into_inner_body(
         Data::Enum(ref e) => {
             let field_mappings = e.variants.iter().map(|v| {
                 let ident = &v.ident;
-                match &v.fields {
-                    &Fields::Unit => quote! {
-                        #result_name::#ident => #inner_name::#ident,
-                    },
-                    _ => unimplemented!("Just unit fields are supported"),
+                let ident_str = ident.to_string();
+                let bindings = variant_bindings(&v.fields);
+                let pattern = variant_pattern(&v.fields, &bindings, "");
+                let field_list = combine_fields(bindings.iter().map(|(member, binding)| {
+                    (
+                        binding.to_token_stream(),
+                        binding.clone(),
+                        member.to_string(),
+                    )
+                }))
+                .map(|(fields, assign)| {
+                    // Generates e.g.:
+                    // let (radius, height) = radius.combine(height).prepend_variant("Circle")?;
+                    quote! {
+                        let #fields = sealedstruct::prelude::ValidationResultExtensions::prepend_variant(#assign, #ident_str)?;
+                    }
+                });
+                quote! {
+                    #result_name::#ident #pattern => {
+                        #field_list
+                        #inner_name::#ident #pattern
+                    }
                 }
             });
             quote! {
@@ -299,7 +688,7 @@ fn create_result_into_inner_body(
                 })
             }
         }
-        Data::Union(_) => unimplemented!(),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 fn create_inner_into_raw_body(data: &Data, inner_name: &Ident, raw_name: &Ident) -> TokenStream {
@@ -325,18 +714,18 @@ fn create_inner_into_raw_body(data: &Data, inner_name: &Ident, raw_name: &Ident)
                     #raw_name { #(#field_mappings)* }
                 }
             }
-            Fields::Unit => {
-                unimplemented!("Unit-Structs are not supported yet");
-            }
+            Fields::Unit => unreachable!("Rejected by ensure_supported"),
         },
         Data::Enum(ref e) => {
             let field_mappings = e.variants.iter().map(|v| {
                 let ident = &v.ident;
-                match &v.fields {
-                    &Fields::Unit => quote! {
-                        #inner_name::#ident => #raw_name::#ident,
-                    },
-                    _ => unimplemented!("Just unit fields are supported"),
+                let bindings = variant_bindings(&v.fields);
+                let pattern = variant_pattern(&v.fields, &bindings, "");
+                let field_mappings = bindings.iter().map(|(member, binding)| {
+                    quote! { #member: sealedstruct::Sealable::open(#binding), }
+                });
+                quote! {
+                    #inner_name::#ident #pattern => #raw_name::#ident { #(#field_mappings)* },
                 }
             });
             quote! {
@@ -345,7 +734,7 @@ fn create_inner_into_raw_body(data: &Data, inner_name: &Ident, raw_name: &Ident)
                 }
             }
         }
-        Data::Union(_) => unimplemented!(),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 fn create_inner(
@@ -429,19 +818,32 @@ fn create_inner(
                     #vis struct #inner_type(#(#recurse)*);
                 }
             }
-            Fields::Unit => unimplemented!(),
+            Fields::Unit => unreachable!("Rejected by ensure_supported"),
         },
         Data::Enum(ref e) => {
-            let recurse = e.variants.iter().map(|variant| match &variant.fields {
-                Fields::Named(_x) => {
-                    // let recurse = x.named.iter().map(|f| quote!());
-                    // quote!(#(#recurse)*);
-                    unimplemented!("Named enum fields are not supported");
-                }
-                Fields::Unnamed(_x) => unimplemented!("Unnamed enum fields are not supported"),
-                Fields::Unit => {
-                    let x = &variant.ident;
-                    quote! {#x,}
+            let recurse = e.variants.iter().map(|variant| {
+                let x = &variant.ident;
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let recurse = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            let ty = &f.ty;
+                            quote_spanned! {f.span()=>
+                                #name: <#ty as sealedstruct::Sealable>::Target,
+                            }
+                        });
+                        quote! {#x { #(#recurse)* },}
+                    }
+                    Fields::Unnamed(fields) => {
+                        let recurse = fields.unnamed.iter().map(|f| {
+                            let ty = &f.ty;
+                            quote_spanned! {f.span()=>
+                                <#ty as sealedstruct::Sealable>::Target,
+                            }
+                        });
+                        quote! {#x(#(#recurse)*),}
+                    }
+                    Fields::Unit => quote! {#x,},
                 }
             });
             quote! {
@@ -451,7 +853,7 @@ fn create_inner(
                 }
             }
         }
-        Data::Union(_) => unimplemented!(),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 
@@ -486,15 +888,32 @@ fn create_result(data: &Data, result_type: TokenStream) -> TokenStream {
                 }
             }
 
-            Fields::Unit => unimplemented!("Unit-Struct not supported"),
+            Fields::Unit => unreachable!("Rejected by ensure_supported"),
         },
         Data::Enum(ref e) => {
-            let recurse = e.variants.iter().map(|variant| match &variant.fields {
-                Fields::Named(_) => unimplemented!("Enums with named fields are not supported"),
-                Fields::Unnamed(_) => unimplemented!("Enums with unnamed fields are not supported"),
-                Fields::Unit => {
-                    let x = &variant.ident;
-                    quote! {#x,}
+            let recurse = e.variants.iter().map(|variant| {
+                let x = &variant.ident;
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let recurse = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            let ty = &f.ty;
+                            quote_spanned! {f.span()=>
+                                #name: sealedstruct::Result<<#ty as sealedstruct::Sealable>::Target>,
+                            }
+                        });
+                        quote! {#x { #(#recurse)* },}
+                    }
+                    Fields::Unnamed(fields) => {
+                        let recurse = fields.unnamed.iter().map(|f| {
+                            let ty = &f.ty;
+                            quote_spanned! {f.span()=>
+                                sealedstruct::Result<<#ty as sealedstruct::Sealable>::Target>,
+                            }
+                        });
+                        quote! {#x(#(#recurse)*),}
+                    }
+                    Fields::Unit => quote! {#x,},
                 }
             });
             quote! {
@@ -503,7 +922,7 @@ fn create_result(data: &Data, result_type: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 
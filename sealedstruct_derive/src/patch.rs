@@ -0,0 +1,175 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Data, DataStruct, DeriveInput, Fields, Generics};
+
+/// Implements `Patchable` for the raw type
+///
+/// With `#[sealed(patch)]`, `{Structname}Patch` is generated, where every field is optional, along with
+/// `{Structname}::apply_patch`. Otherwise the patch replaces the whole value.
+/// `#[sealed(patch(serde))]` derives `Serialize` and `Deserialize` for the patch as well.
+pub(crate) fn expand(
+    input: &DeriveInput,
+    enabled: bool,
+    serde: bool,
+    facade_name: &Ident,
+    patch_name: &Ident,
+) -> syn::Result<TokenStream> {
+    let raw_name = &input.ident;
+    if !enabled {
+        return Ok(replace_on_patch(raw_name, &input.generics));
+    }
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                raw_name,
+                "#[sealed(patch)] is only supported on structs with named fields",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[sealed(patch)] is not supported on generic types",
+        ));
+    }
+    #[cfg(not(feature = "serde"))]
+    if serde {
+        return Err(syn::Error::new_spanned(
+            raw_name,
+            "#[sealed(patch(serde))] requires the `serde` feature",
+        ));
+    }
+
+    let declarations = fields.named.iter().map(|field| {
+        let (vis, ident, ty) = (&field.vis, &field.ident, &field.ty);
+        let serde_attrs = if serde {
+            field_serde_attrs(field)
+        } else {
+            TokenStream::new()
+        };
+        quote! {
+            #serde_attrs
+            #vis #ident: Option<<#ty as sealedstruct::Patchable>::Patch>
+        }
+    });
+    let applications = fields.named.iter().map(|field| {
+        let ident = &field.ident;
+        quote! {
+            if let Some(value) = patch.#ident {
+                sealedstruct::Patchable::patch(&mut self.#ident, value);
+            }
+        }
+    });
+    let container_serde_attrs = if serde {
+        container_serde_attrs(input)
+    } else {
+        TokenStream::new()
+    };
+    let vis = &input.vis;
+    let doc = format!("Partial update of [{raw_name}]. Fields set to `None` are kept.");
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Default)]
+        #container_serde_attrs
+        #vis struct #patch_name {
+            #(#declarations,)*
+        }
+
+        impl sealedstruct::Patchable for #raw_name {
+            type Patch = #patch_name;
+
+            fn patch(&mut self, patch: #patch_name) {
+                #(#applications)*
+            }
+        }
+
+        impl #facade_name {
+            /// Applies the patch to the raw value and seals the result again
+            ///
+            /// The errors are the same as those of sealing the patched raw value.
+            pub fn apply_patch(self, patch: #patch_name) -> sealedstruct::Result<Self> {
+                let mut raw = <#raw_name as sealedstruct::Sealable>::open(self);
+                sealedstruct::Patchable::patch(&mut raw, patch);
+                sealedstruct::Sealable::seal(raw)
+            }
+        }
+    })
+}
+
+/// Patches of types without `#[sealed(patch)]` replace the whole value
+pub(crate) fn replace_on_patch(name: &Ident, generics: &Generics) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics sealedstruct::Patchable for #name #ty_generics #where_clause {
+            type Patch = Self;
+
+            fn patch(&mut self, patch: Self) {
+                *self = patch;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn container_serde_attrs(input: &DeriveInput) -> TokenStream {
+    let forwarded = forwarded_serde_attrs(&input.attrs, &["rename_all", "deny_unknown_fields"]);
+    quote! {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        #(#forwarded)*
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn container_serde_attrs(_input: &DeriveInput) -> TokenStream {
+    TokenStream::new()
+}
+
+/// Missing fields are `None`, while `null` is passed on to the patch of the field,
+/// so optional fields can be cleared
+#[cfg(feature = "serde")]
+fn field_serde_attrs(field: &syn::Field) -> TokenStream {
+    let forwarded = forwarded_serde_attrs(&field.attrs, &["rename", "alias"]);
+    quote! {
+        #[serde(
+            default,
+            deserialize_with = "sealedstruct::patch::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )]
+        #(#forwarded)*
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn field_serde_attrs(_field: &syn::Field) -> TokenStream {
+    TokenStream::new()
+}
+
+/// `#[serde(...)]` attributes of the raw type, which only contain the allowed options
+///
+/// Other options, e.g. `default` or `with`, don't apply to the patch.
+#[cfg(feature = "serde")]
+fn forwarded_serde_attrs<'a>(
+    attrs: &'a [syn::Attribute],
+    allowed: &'a [&str],
+) -> impl Iterator<Item = &'a syn::Attribute> {
+    use syn::{punctuated::Punctuated, Meta, Token};
+
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter(move |attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .is_ok_and(|options| {
+                    options.iter().all(|option| {
+                        allowed
+                            .iter()
+                            .any(|allowed| option.path().is_ident(allowed))
+                    })
+                })
+        })
+}
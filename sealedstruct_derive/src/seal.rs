@@ -1,33 +1,80 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::{quote, quote_spanned};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
+use syn::{parse_quote_spanned, Data, DeriveInput, Fields};
 
-pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    // Parse the input tokens into a syntax tree.
-    let input = parse_macro_input!(input as DeriveInput);
-    if let syn::Visibility::Inherited = input.vis {
-        panic!("Raw-Struct mustn't be private. Deriving 'Seal' only makes sense if generated Sealed* is in submodule");
+use crate::attrs::{ContainerAttrs, Names};
+use crate::nested::{
+    combine_fields, create_setters, create_wrapper, ensure_not_private, ensure_supported, local,
+    sealed_derives, variant_bindings, variant_pattern,
+};
+
+pub(crate) fn expand(input: DeriveInput, container: ContainerAttrs) -> syn::Result<TokenStream> {
+    ensure_supported(&input)?;
+    ensure_not_private(&input)?;
+    if container.check_by_clone {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "#[sealed(check_by_clone)] requires #[sealed(mode = \"nested\")], as wrapped types are checked by their `Validator`",
+        ));
     }
 
+    let Names {
+        facade: facade_name,
+        wrapper: wrapper_name,
+        result: result_name,
+        patch: patch_name,
+        ..
+    } = container.names(&input.ident)?;
+    let patch = crate::patch::expand(
+        &input,
+        container.patch,
+        container.patch_serde,
+        &facade_name,
+        &patch_name,
+    )?;
+    // The fields of a wrapped raw type needn't implement `Merge`
+    let merge = if container.merge {
+        crate::merge::replace_on_merge(&input.ident, &input.generics)
+    } else {
+        TokenStream::new()
+    };
+    let from_env = crate::env::expand(&input, container.env)?;
+    // The sealed type wraps the raw type, so the derives are added to the wrapper
+    let wrapper = create_wrapper(&wrapper_name, &sealed_derives(&input)?);
+
     // Used in the quasi-quotation below as `#name`.
     let raw_name = input.ident;
-    let raw_name_str = raw_name.to_string();
-    if !raw_name_str.ends_with("Raw") {
-        panic!("Struct name must end with 'Raw'");
-    }
 
     let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
-    let struct_name_str = &raw_name_str[..(raw_name_str.len() - 3)];
-    let facade_name = syn::Ident::new(struct_name_str, raw_name.span());
-    let wrapper_name = syn::Ident::new(&format!("{struct_name_str}Wrapper"), raw_name.span());
-    let result_name = syn::Ident::new(&format!("{struct_name_str}Result"), raw_name.span());
+    // Sealable compares raw and sealed values, so the raw type has to implement PartialEq.
+    // The bound is spanned, so a missing implementation is reported on the raw type.
+    let mut sealable_generics = input.generics.clone();
+    sealable_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote_spanned!(raw_name.span()=> #raw_name #ty_generics: std::cmp::PartialEq));
+    let sealable_where_clause = &sealable_generics.where_clause;
     let input_vis = input.vis;
 
     // Generate an expression to sum up the heap size of each field.
     let result = create_result(&input.data, quote! { #result_name });
-    let result_into_wrapper =
-        create_result_into_wrapper_body(&input.data, &wrapper_name, &raw_name, &result_name);
+    let result_into_wrapper = create_result_into_wrapper_body(&input.data, &result_name);
+    let setters = create_setters(
+        &input.data,
+        "Replaces the field and checks the raw value again",
+        |field, ident, setter| {
+            let ty = &field.ty;
+            let raw = local("raw");
+            Ok(quote! {
+                fn #setter(self, #ident: #ty) -> sealedstruct::Result<Self> {
+                    let mut #raw = self.0;
+                    #raw.#ident = #ident;
+                    Self::new(#raw)
+                }
+            })
+        },
+    )?;
 
     #[cfg(feature = "serde")]
     let serde_wrapper = {
@@ -61,6 +108,12 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
         #serde_wrapper
 
+        #patch
+
+        #merge
+
+        #from_env
+
         #input_vis type #facade_name #ty_generics = #wrapper_name<#raw_name #ty_generics>;
         impl #impl_generics TryFrom<#raw_name  #ty_generics> for #facade_name  #ty_generics {
             type Error = sealedstruct::ValidationErrors;
@@ -78,6 +131,10 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         }
 
         impl #impl_generics #wrapper_name<#raw_name #ty_generics> {
+            pub fn new(raw: #raw_name #ty_generics) -> sealedstruct::Result<Self> {
+                raw.try_into()
+            }
+
             fn new_unchecked(raw: #raw_name #ty_generics) -> Self {
                 #[cfg(debug_assertions)]
                 if let Err(e) = sealedstruct::Validator::check(&raw) {
@@ -89,6 +146,8 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             pub fn into_inner(self) -> #raw_name #ty_generics {
                 self.0
             }
+
+            #setters
         }
 
         impl<T: std::fmt::Display> std::fmt::Display for #wrapper_name<T> {
@@ -97,8 +156,85 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             }
         }
 
-        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default, sealedstruct::IntoNested, Ord, PartialOrd)]
-        pub struct #wrapper_name<T>(T);
+        #wrapper
+
+        // Already sealed values are valid by definition
+        impl<T> sealedstruct::Patchable for #wrapper_name<T> {
+            type Patch = Self;
+
+            fn patch(&mut self, patch: Self) {
+                *self = patch;
+            }
+        }
+
+        impl<T> sealedstruct::Merge for #wrapper_name<T> {
+            fn merge(&mut self, other: Self) {
+                *self = other;
+            }
+        }
+
+        impl<T: std::cmp::PartialEq> sealedstruct::Sealable for #wrapper_name<T> {
+            type Target = Self;
+
+            fn seal(self) -> sealedstruct::Result<Self> {
+                Ok(self)
+            }
+
+            fn open(sealed: Self) -> Self {
+                sealed
+            }
+
+            fn partial_eq(&self, other: &Self) -> bool {
+                self == other
+            }
+        }
+
+        impl<T: std::cmp::PartialEq> sealedstruct::TrySealable for #wrapper_name<T> {
+            fn try_seal(self) -> sealedstruct::SealResult<Self> {
+                Ok(self)
+            }
+        }
+
+        impl<T: std::cmp::PartialEq> sealedstruct::Checkable for #wrapper_name<T> {
+            fn check_sealable(&self) -> sealedstruct::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl #impl_generics sealedstruct::Sealable for #raw_name #ty_generics #sealable_where_clause {
+            type Target = #facade_name #ty_generics;
+
+            fn seal(self) -> sealedstruct::Result<Self::Target> {
+                self.try_into()
+            }
+
+            fn open(sealed: Self::Target) -> Self {
+                sealed.into_inner()
+            }
+
+            fn partial_eq(&self, other: &Self::Target) -> bool {
+                self == &other.0
+            }
+        }
+
+        impl #impl_generics sealedstruct::TrySealable for #raw_name #ty_generics #sealable_where_clause {
+            fn try_seal(self) -> sealedstruct::SealResult<Self> {
+                match sealedstruct::Validator::check(&self) {
+                    Ok(()) => Ok(#wrapper_name(self)),
+                    Err(errors) => Err(sealedstruct::SealError::new(errors, self)),
+                }
+            }
+        }
+
+        impl #impl_generics sealedstruct::Checkable for #raw_name #ty_generics #sealable_where_clause {
+            fn check_sealable(&self) -> sealedstruct::Result<()> {
+                sealedstruct::Validator::check(self)
+            }
+        }
+
+        impl #impl_generics sealedstruct::Sealed for #facade_name #ty_generics #sealable_where_clause {
+            type Raw = #raw_name #ty_generics;
+        }
 
         impl<T> std::ops::Deref for #wrapper_name<T> {
             type Target = T;
@@ -115,94 +251,56 @@ pub fn derive_seal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         }
     };
 
-    // Hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    Ok(expanded)
 }
 
-fn create_result_into_wrapper_body(
-    data: &Data,
-    wrapper_name: &Ident,
-    raw_name: &Ident,
-    result_name: &Ident,
-) -> TokenStream {
+fn create_result_into_wrapper_body(data: &Data, result_name: &Ident) -> TokenStream {
     match *data {
         Data::Struct(ref data) => {
-            match data.fields {
-                Fields::Named(ref fields) => {
-                    let mut ident_iter = fields.named.iter().flat_map(|f| f.ident.clone());
-                    match ident_iter.next() {
-                        Some(first) => {
-                            let first_string = first.to_string();
-                            let assign = ident_iter.fold(
-                                quote!{
-                                    sealedstruct::prelude::ValidationResultExtensions::prepend_path(input.#first, #first_string)
-                                },
-                                |assign, next| {
-                                    let next_text = next.to_string();
-
-                                    quote! { sealedstruct::prelude::ValidationResultExtensions::combine(#assign,
-                                        sealedstruct::prelude::ValidationResultExtensions::prepend_path(input.#next, #next_text))
-                                    }
-                                },
-                            );
-                            // Generates e.g.:
-                            // input.foo.prependPath("foo").combine(input.bar.prependPath("bar")).combine(input.baz.prepend_path("baz"));
-                            quote! {
-                                #assign.map(|_| ())
-                            }
-                        }
-                        _ => quote! { Ok(())},
-                    }
-                }
-                Fields::Unnamed(ref fields) => {
-                    let mut ident_iter = (0..fields.unnamed.len()).map(|f| {
-                        let index = Index::from(f);
-                        (quote! { input.#index }, f.to_string())
-                    });
-                    match ident_iter.next() {
-                        Some((first_acc, first_label)) => {
-                            let assign = ident_iter.fold(
-                                quote!{
-                                    sealedstruct::prelude::ValidationResultExtensions::prepend_path(#first_acc, #first_label)
-                                },
-                                |assign, (next_acc, next_label)| {
-                                    quote! { sealedstruct::prelude::ValidationResultExtensions::combine(#assign,
-                                        sealedstruct::prelude::ValidationResultExtensions::prepend_path(#next_acc, #next_label))
-                                    }
-                                },
-                            );
-                            // Generates e.g.:
-                            // input.0.prependPath("0").combine(input.1.prependPath("1")).combine(input.2.prepend_path("2"));
-                            quote! {
-                                #assign.map(|_| ())
-                            }
-                        }
-                        _ => TokenStream::new(),
-                    }
-                }
-                Fields::Unit => {
-                    // Unit structs cannot own more than 0 bytes of heap memory.
-                    quote! { Ok(())}
-                }
+            // Generates e.g.:
+            // input.foo.prepend_field("foo").combine(input.bar.prepend_field("bar")).map(|_| ())
+            let assign =
+                combine_fields(variant_bindings(&data.fields).into_iter().map(
+                    |(member, binding)| (quote! { input.#member }, binding, member.to_string()),
+                ));
+            match assign {
+                Some((_, assign)) => quote! { #assign.map(|_| ()) },
+                // Unit structs cannot own more than 0 bytes of heap memory.
+                None => quote! { Ok(()) },
             }
         }
         Data::Enum(ref e) => {
             let field_mappings = e.variants.iter().map(|v| {
                 let ident = &v.ident;
-                match &v.fields {
-                    &Fields::Unit => quote! {
-                        #result_name::#ident => #raw_name::#ident,
-                    },
-                    _ => unimplemented!("Just unit fields are supported"),
+                let ident_str = ident.to_string();
+                let bindings = variant_bindings(&v.fields);
+                let pattern = variant_pattern(&v.fields, &bindings, "");
+                let assign = combine_fields(bindings.iter().map(|(member, binding)| {
+                    (
+                        binding.to_token_stream(),
+                        binding.clone(),
+                        member.to_string(),
+                    )
+                }))
+                .map(|(_, assign)| {
+                    // Generates e.g.:
+                    // x0.prepend_field("0").combine(x1.prepend_field("1")).prepend_variant("Relative").map(|_| ())
+                    quote! {
+                        sealedstruct::prelude::ValidationResultExtensions::prepend_variant(#assign, #ident_str).map(|_| ())
+                    }
+                })
+                .unwrap_or_else(|| quote! { Ok(()) });
+                quote! {
+                    #result_name::#ident #pattern => #assign,
                 }
             });
             quote! {
-                Ok(#wrapper_name(match input {
+                match input {
                     #(#field_mappings)*
-                }))
+                }
             }
         }
-        Data::Union(_) => unimplemented!(),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
 
@@ -235,15 +333,30 @@ fn create_result(data: &Data, result_type: TokenStream) -> TokenStream {
                 }
             }
 
-            Fields::Unit => unimplemented!("Unit-Struct not supported"),
+            Fields::Unit => unreachable!("Rejected by ensure_supported"),
         },
         Data::Enum(ref e) => {
-            let recurse = e.variants.iter().map(|variant| match &variant.fields {
-                Fields::Named(_) => unimplemented!("Enums with named fields are not supported"),
-                Fields::Unnamed(_) => unimplemented!("Enums with unnamed fields are not supported"),
-                Fields::Unit => {
-                    let x = &variant.ident;
-                    quote! {#x,}
+            let recurse = e.variants.iter().map(|variant| {
+                let x = &variant.ident;
+                match &variant.fields {
+                    Fields::Named(fields) => {
+                        let recurse = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            quote_spanned! {f.span()=>
+                                #name: sealedstruct::Result<()>,
+                            }
+                        });
+                        quote! {#x { #(#recurse)* },}
+                    }
+                    Fields::Unnamed(fields) => {
+                        let recurse = fields.unnamed.iter().map(|f| {
+                            quote_spanned! {f.span()=>
+                                sealedstruct::Result<()>
+                            }
+                        });
+                        quote! {#x(#(#recurse,)*),}
+                    }
+                    Fields::Unit => quote! {#x,},
                 }
             });
             quote! {
@@ -252,6 +365,6 @@ fn create_result(data: &Data, result_type: TokenStream) -> TokenStream {
                 }
             }
         }
-        Data::Union(_) => unimplemented!("Unions are not supported"),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
     }
 }
@@ -0,0 +1,57 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
+
+use crate::nested::ensure_supported;
+
+pub fn sealed(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    // Parse the input tokens into a syntax tree.
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(args.into(), input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: TokenStream, mut input: DeriveInput) -> syn::Result<TokenStream> {
+    ensure_supported(&input)?;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "Generic types are not supported by #[sealed]. Use derive(Nested) instead",
+        ));
+    }
+
+    // The sealed type is generated by `Nested` as `{Structname}`
+    input.ident = Ident::new(&format!("{}Raw", input.ident), input.ident.span());
+    match &mut input.data {
+        Data::Struct(data) => raw_fields(&mut data.fields),
+        Data::Enum(data) => data
+            .variants
+            .iter_mut()
+            .for_each(|variant| raw_fields(&mut variant.fields)),
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
+    }
+
+    let options = if args.is_empty() {
+        TokenStream::new()
+    } else {
+        quote! { #[sealed(#args)] }
+    };
+
+    Ok(quote! {
+        #[derive(sealedstruct::Nested, sealedstruct::TryIntoNested)]
+        #options
+        #input
+    })
+}
+
+/// Replaces the type of each field by its raw type
+fn raw_fields(fields: &mut Fields) {
+    for field in fields.iter_mut() {
+        let ty = &field.ty;
+        field.ty = parse_quote! { <#ty as sealedstruct::Sealed>::Raw };
+    }
+}
@@ -1,92 +1,385 @@
 use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Index};
+use syn::{
+    parse_macro_input, parse_quote, parse_quote_spanned, Data, DeriveInput, Field, Fields, Ident,
+};
+
+use crate::attrs;
+use crate::nested::{
+    add_trait_bounds, all_fields, combine_fields, combine_fields_with, ensure_supported, local,
+    variant_bindings, variant_pattern,
+};
+
+/// What a failed seal returns
+#[derive(Clone, Copy)]
+enum Sealing {
+    /// Only the errors, for `TryIntoNested::try_into_nested`
+    Errors,
+    /// The errors and the raw value, for `TrySealable::try_seal`
+    KeepRaw,
+}
 
 pub fn derive_try_into_nested(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // Parse the input tokens into a syntax tree.
     let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    ensure_supported(&input)?;
+
+    let container = attrs::ContainerAttrs::parse(&input.attrs)?;
+    let attrs::Names {
+        inner: inner_name,
+        wrapper: wrapper_name,
+        ..
+    } = container.names(&input.ident)?;
+    let try_sealable_generics =
+        field_bound_generics(&input, parse_quote!(sealedstruct::TrySealable));
+    let checkable_generics = field_bound_generics(&input, parse_quote!(sealedstruct::Checkable));
 
     // Used in the quasi-quotation below as `#name`.
     let raw_struct_name = input.ident;
-    let raw_struct_name_str = raw_struct_name.to_string();
-    if !raw_struct_name_str.ends_with("Raw") {
-        panic!("Struct name must end with 'Raw'");
-    }
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
-    let struct_name_str = &raw_struct_name_str[..(raw_struct_name_str.len() - 3)];
-    let struct_name = syn::Ident::new(struct_name_str, raw_struct_name.span());
-    let inner_name = syn::Ident::new(&format!("{struct_name}Inner"), raw_struct_name.span());
-    let result_name = syn::Ident::new(&format!("{struct_name}Result"), raw_struct_name.span());
+    let (try_impl_generics, _, try_where_clause) = try_sealable_generics.split_for_impl();
+    let (check_impl_generics, _, check_where_clause) = checkable_generics.split_for_impl();
 
-    // Generate an expression to sum up the heap size of each field.
-    let result = create_fields(&input.data, &result_name);
+    let result = create_fields(&input.data, &inner_name, Sealing::Errors)?;
+    let result_or_raw = create_fields(&input.data, &inner_name, Sealing::KeepRaw)?;
+    let check = create_checks(&input.data)?;
 
-    let expanded = quote! {
+    let checks = container
+        .checks
+        .iter()
+        .map(|check| quote! { #check(self) })
+        .reduce(|acc, check| {
+            quote! {
+                sealedstruct::prelude::ValidationResultExtensions::combine(#acc, #check).map(|_| ())
+            }
+        });
+    let (rules, nested, fields, errors) = (
+        local("rules"),
+        local("nested"),
+        local("fields"),
+        local("errors"),
+    );
+    let (body, body_or_raw, check, check_rules) = match checks {
+        None => (result, result_or_raw, check, TokenStream::new()),
+        // Cross-field checks borrow the raw value, so they run before sealing consumes it.
+        // Their errors are only reported, if all fields could be sealed.
+        Some(checks) => (
+            quote! {
+                let #rules = <Self as sealedstruct::TryIntoNested>::check_rules(&self);
+                let #nested: sealedstruct::Result<Self::Target> = #result;
+                let #nested = #nested?;
+                #rules?;
+                Ok(#nested)
+            },
+            quote! {
+                let #rules = <Self as sealedstruct::TryIntoNested>::check_rules(&self);
+                let #nested: std::result::Result<#inner_name #ty_generics, sealedstruct::SealError<Self>> = #result_or_raw;
+                let #nested = #nested?;
+                match #rules {
+                    Ok(()) => Ok(#nested),
+                    Err(#errors) => Err(sealedstruct::SealError::new(#errors, #nested.into())),
+                }
+            },
+            quote! {
+                let #fields: sealedstruct::Result<()> = { #check };
+                #fields?;
+                <Self as sealedstruct::TryIntoNested>::check_rules(self)
+            },
+            quote! {
+                fn check_rules(&self) -> sealedstruct::Result<()> {
+                    #checks
+                }
+            },
+        ),
+    };
+
+    let try_into_nested = quote! {
         impl #impl_generics sealedstruct::TryIntoNested for #raw_struct_name #ty_generics #where_clause {
             type Target = #inner_name #ty_generics;
 
             fn try_into_nested(self) -> sealedstruct::Result<Self::Target> {
-                #result
+                #body
+            }
+
+            #check_rules
+        }
+    };
+    // `derive(Nested)` implements both traits with this option, so only the conflict is reported
+    if container.check_by_clone {
+        let error = syn::Error::new_spanned(
+            &raw_struct_name,
+            "#[sealed(check_by_clone)] is for handwritten implementations of TryIntoNested, the derive implements TrySealable and Checkable itself",
+        )
+        .into_compile_error();
+        return Ok(quote! {
+            #error
+            #try_into_nested
+        });
+    }
+
+    let expanded = quote! {
+        #try_into_nested
+
+        impl #try_impl_generics sealedstruct::TrySealable for #raw_struct_name #ty_generics #try_where_clause {
+            fn try_seal(self) -> sealedstruct::SealResult<Self> {
+                let #nested: std::result::Result<#inner_name #ty_generics, sealedstruct::SealError<Self>> = {
+                    #body_or_raw
+                };
+                #nested.map(#wrapper_name)
+            }
+        }
+
+        impl #check_impl_generics sealedstruct::Checkable for #raw_struct_name #ty_generics #check_where_clause {
+            fn check_sealable(&self) -> sealedstruct::Result<()> {
+                #check
             }
         }
     };
 
-    // Hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    Ok(expanded)
 }
 
-fn create_fields(data: &Data, result_name: &Ident) -> TokenStream {
-    match *data {
-        Data::Struct(ref data) => {
-            match data.fields {
-                Fields::Named(ref fields) => {
-                    let recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        quote_spanned! {f.span()=>
-                            #name: sealedstruct::Sealable::seal(self.#name),
-                        }
-                    });
+/// Generics of the `TrySealable` and `Checkable` impls, which require `bound` of all fields
+///
+/// The bounds are spanned, so a field without `bound` is reported on its type.
+fn field_bound_generics(input: &DeriveInput, bound: syn::Path) -> syn::Generics {
+    let mut generics = add_trait_bounds(
+        input.generics.clone(),
+        &[parse_quote!(sealedstruct::Sealable)],
+    );
+    let where_clause = generics.make_where_clause();
+    for field in all_fields(&input.data) {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote_spanned!(ty.span()=> #ty: #bound));
+    }
+    generics
+}
+
+/// Seals the field, running all checks from its `#[sealed(...)]` attributes on the raw value first
+fn seal_field(field: &Field, value: TokenStream, sealing: Sealing) -> syn::Result<TokenStream> {
+    let checks = attrs::combined_field_checks(field, &value)?;
+    Ok(match sealing {
+        Sealing::Errors => {
+            let seal = quote_spanned! {field.span()=>
+                sealedstruct::Sealable::seal(#value)
+            };
+            match checks {
+                Some(checks) => quote! {
+                    sealedstruct::prelude::ValidationResultExtensions::combine(#checks, #seal).map(|(_, x)| x)
+                },
+                None => seal,
+            }
+        }
+        Sealing::KeepRaw => {
+            let seal = quote_spanned! {field.span()=>
+                sealedstruct::TrySealable::try_seal(#value)
+            };
+            match checks {
+                // Checks borrow the value, so they run before it is moved into try_seal
+                Some(checks) => {
+                    let check = local("check");
                     quote! {
-                        #result_name {
-                            #(#recurse)*
-                        }.into()
-                    }
-                }
-                Fields::Unnamed(ref fields) => {
-                    let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let index = Index::from(i);
-                        quote_spanned! {f.span()=>
-                            sealedstruct::Sealable::seal(self.#index),
+                        {
+                            let #check = #checks;
+                            sealedstruct::SealResultExtensions::with_check(#seal, #check)
                         }
-                    });
-                    quote! {
-                        #result_name(#(#recurse)*).into()
                     }
                 }
-                Fields::Unit => {
-                    // Unit structs cannot own more than 0 bytes of heap memory.
-                    quote!()
+                None => seal,
+            }
+        }
+    })
+}
+
+/// Seals all fields bound by `pattern`. With [Sealing::KeepRaw], the raw value is rebuilt
+/// with `raw_path #pattern` if any of them fails, opening the fields which were sealed successfully.
+///
+/// Generates e.g.:
+/// ```text
+/// match foo.try_seal().prepend_field("foo").combine(bar.try_seal().prepend_field("bar")) {
+///     Ok((foo, bar)) => Ok(Inner { foo, bar }),
+///     Err(e) => Err(e.map_raw(|(foo, bar)| Self { foo, bar })),
+/// }
+/// ```
+fn seal_fields(
+    fields: &Fields,
+    raw_path: TokenStream,
+    inner_path: TokenStream,
+    variant: Option<String>,
+    sealing: Sealing,
+) -> syn::Result<TokenStream> {
+    let bindings = variant_bindings(fields);
+    let pattern = variant_pattern(fields, &bindings, "");
+    let seals = fields
+        .iter()
+        .zip(bindings.iter())
+        .map(|(field, (member, binding))| {
+            Ok((
+                seal_field(field, binding.to_token_stream(), sealing)?,
+                binding.clone(),
+                member.to_string(),
+            ))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let extensions = match sealing {
+        Sealing::Errors => quote! { sealedstruct::prelude::ValidationResultExtensions },
+        Sealing::KeepRaw => quote! { sealedstruct::SealResultExtensions },
+    };
+    Ok(match combine_fields_with(&extensions, seals) {
+        Some((field_list, assign)) => {
+            let assign = match variant {
+                Some(variant) => quote! { #extensions::prepend_variant(#assign, #variant) },
+                None => assign,
+            };
+            let e = local("e");
+            let raw = match sealing {
+                Sealing::Errors => quote! { #e },
+                Sealing::KeepRaw => quote! { #e.map_raw(|#field_list| #raw_path #pattern) },
+            };
+            quote! {
+                match #assign {
+                    Ok(#field_list) => Ok(#inner_path #pattern),
+                    Err(#e) => Err(#raw),
+                }
+            }
+        }
+        None => quote! { Ok(#inner_path #pattern) },
+    })
+}
+
+fn create_fields(data: &Data, inner_name: &Ident, sealing: Sealing) -> syn::Result<TokenStream> {
+    Ok(match *data {
+        Data::Struct(ref data) => {
+            let bindings = variant_bindings(&data.fields);
+            let pattern = variant_pattern(&data.fields, &bindings, "");
+            let seal = seal_fields(
+                &data.fields,
+                quote! { Self },
+                quote! { #inner_name },
+                None,
+                sealing,
+            )?;
+            quote! {
+                {
+                    let Self #pattern = self;
+                    #seal
                 }
             }
         }
         Data::Enum(ref e) => {
-            let field_mappings = e.variants.iter().map(|v| {
-                let ident = &v.ident;
-                match &v.fields {
-                    &Fields::Unit => quote! {
-                        Self::#ident => #result_name::#ident,
-                    },
-                    _ => unimplemented!("Just unit fields are supported"),
+            let field_mappings = e
+                .variants
+                .iter()
+                .map(|v| {
+                    let ident = &v.ident;
+                    let bindings = variant_bindings(&v.fields);
+                    let pattern = variant_pattern(&v.fields, &bindings, "");
+                    let seal = seal_fields(
+                        &v.fields,
+                        quote! { Self::#ident },
+                        quote! { #inner_name::#ident },
+                        Some(ident.to_string()),
+                        sealing,
+                    )?;
+                    Ok(quote! {
+                        Self::#ident #pattern => #seal,
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#field_mappings)*
                 }
-            });
+            }
+        }
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
+    })
+}
+
+/// Checks the field by reference: First its `#[sealed(...)]` attributes, then the field itself
+fn check_field(field: &Field, binding: &Ident) -> syn::Result<TokenStream> {
+    let check = quote_spanned! {field.span()=>
+        sealedstruct::Checkable::check_sealable(#binding)
+    };
+    Ok(
+        match attrs::combined_field_checks(field, &quote! { (*#binding) })? {
+            Some(checks) => quote! {
+                sealedstruct::prelude::ValidationResultExtensions::combine(#checks, #check).map(|_| ())
+            },
+            None => check,
+        },
+    )
+}
+
+/// Checks all fields bound by reference, reporting the same paths as [seal_fields]
+fn check_fields(fields: &Fields, variant: Option<String>) -> syn::Result<TokenStream> {
+    let bindings = variant_bindings(fields);
+    let checks = fields
+        .iter()
+        .zip(bindings.iter())
+        .map(|(field, (member, binding))| {
+            Ok((
+                check_field(field, binding)?,
+                binding.clone(),
+                member.to_string(),
+            ))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(match combine_fields(checks) {
+        Some((_, assign)) => {
+            let assign = match variant {
+                Some(variant) => quote! {
+                    sealedstruct::prelude::ValidationResultExtensions::prepend_variant(#assign, #variant)
+                },
+                None => assign,
+            };
+            quote! { #assign.map(|_| ()) }
+        }
+        None => quote! { Ok(()) },
+    })
+}
+
+fn create_checks(data: &Data) -> syn::Result<TokenStream> {
+    Ok(match *data {
+        Data::Struct(ref data) => {
+            let bindings = variant_bindings(&data.fields);
+            let pattern = variant_pattern(&data.fields, &bindings, "");
+            let check = check_fields(&data.fields, None)?;
+            quote! {
+                let Self #pattern = self;
+                #check
+            }
+        }
+        Data::Enum(ref e) => {
+            let field_mappings = e
+                .variants
+                .iter()
+                .map(|v| {
+                    let ident = &v.ident;
+                    let bindings = variant_bindings(&v.fields);
+                    let pattern = variant_pattern(&v.fields, &bindings, "");
+                    let check = check_fields(&v.fields, Some(ident.to_string()))?;
+                    Ok(quote! {
+                        Self::#ident #pattern => #check,
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
             quote! {
                 match self {
                     #(#field_mappings)*
-                }.into()
+                }
             }
         }
-        Data::Union(_) => unimplemented!(),
-    }
+        Data::Union(_) => unreachable!("Rejected by ensure_supported"),
+    })
 }